@@ -1,4 +1,4 @@
-use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
+use crate::{AddressOverflowError, HeaderTagFlag, HeaderTagHeader, HeaderTagType};
 use core::mem::size_of;
 use multiboot2_common::{MaybeDynSized, Tag};
 
@@ -42,10 +42,30 @@ impl AddressHeaderTag {
         }
     }
 
+    /// Like [`Self::new`] but takes the addresses as `u64`, as it is common
+    /// on 64-bit hosts.
+    ///
+    /// Fails if any address doesn't fit into the 32-bit fields of the tag.
+    pub fn new_u64(
+        flags: HeaderTagFlag,
+        header_addr: u64,
+        load_addr: u64,
+        load_end_addr: u64,
+        bss_end_addr: u64,
+    ) -> Result<Self, AddressOverflowError> {
+        Ok(Self::new(
+            flags,
+            AddressOverflowError::check(header_addr)?,
+            AddressOverflowError::check(load_addr)?,
+            AddressOverflowError::check(load_end_addr)?,
+            AddressOverflowError::check(bss_end_addr)?,
+        ))
+    }
+
     /// Returns the [`HeaderTagType`].
     #[must_use]
     pub const fn typ(&self) -> HeaderTagType {
-        self.header.typ()
+        Self::ID
     }
 
     /// Returns the [`HeaderTagFlag`]s.
@@ -100,7 +120,7 @@ impl Tag for AddressHeaderTag {
 
 #[cfg(test)]
 mod tests {
-    use crate::AddressHeaderTag;
+    use crate::{AddressHeaderTag, AddressOverflowError, HeaderTagFlag};
 
     #[test]
     fn test_assert_size() {
@@ -109,4 +129,16 @@ mod tests {
             2 + 2 + 4 + 4 + 4 + 4 + 4
         );
     }
+
+    #[test]
+    fn test_new_u64() {
+        let tag =
+            AddressHeaderTag::new_u64(HeaderTagFlag::Required, 0x1000, 0x1000, 0x2000, 0x3000)
+                .unwrap();
+        assert_eq!(tag.bss_end_addr(), 0x3000);
+
+        let err =
+            AddressHeaderTag::new_u64(HeaderTagFlag::Required, 0x1000, 0x1000, 0x2000, u64::MAX);
+        assert_eq!(err, Err(AddressOverflowError(u64::MAX)));
+    }
 }
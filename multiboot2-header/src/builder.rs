@@ -1,13 +1,91 @@
 //! Exports a builder [`Builder`].
 
 use crate::{
-    AddressHeaderTag, ConsoleHeaderTag, EfiBootServiceHeaderTag, EntryAddressHeaderTag,
-    EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag, HeaderTagISA,
-    InformationRequestHeaderTag, ModuleAlignHeaderTag, Multiboot2BasicHeader, RelocatableHeaderTag,
+    AddressHeaderTag, AddressOverflowError, ConsoleHeaderTag, ConsoleHeaderTagFlags,
+    EfiBootServiceHeaderTag, EndHeaderTag, EntryAddressHeaderTag, EntryEfi32HeaderTag,
+    EntryEfi64HeaderTag, FramebufferHeaderTag, HeaderTagFlag, HeaderTagHeader, HeaderTagISA,
+    HeaderTagType, InformationRequestHeaderTag, LoadError, MbiTagType, MbiTagTypeId,
+    ModuleAlignHeaderTag, Multiboot2BasicHeader, Multiboot2Header, RelocatableHeaderTag,
+    RelocatableHeaderTagPreference,
 };
 use alloc::boxed::Box;
 use alloc::vec::Vec;
-use multiboot2_common::{new_boxed, DynSizedStructure, MaybeDynSized};
+use core::mem;
+use multiboot2_common::{
+    increase_to_alignment, new_boxed, DynSizedStructure, MaybeDynSized, ALIGNMENT,
+};
+
+/// Conventional sets of tags for common kinds of payloads. See
+/// [`Builder::preset`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Preset {
+    /// An ELF payload booted via legacy BIOS. Adds a [`ModuleAlignHeaderTag`].
+    BiosElf,
+    /// A relocatable payload booted via UEFI that uses the UEFI boot
+    /// services. Adds a [`ModuleAlignHeaderTag`], an
+    /// [`EfiBootServiceHeaderTag`], and a [`RelocatableHeaderTag`] that allows
+    /// any 4 KiB aligned location above 1 MiB.
+    UefiRelocatable,
+    /// A payload booted via legacy BIOS that uses the EGA text mode. Adds a
+    /// [`ModuleAlignHeaderTag`] and a [`ConsoleHeaderTag`].
+    LegacyText,
+}
+
+/// Depths in bits per pixel that loaders commonly provide.
+const COMMON_FRAMEBUFFER_DEPTHS: [u8; 5] = [8, 15, 16, 24, 32];
+
+/// Warnings about a [`FramebufferHeaderTag`] that is valid but may not be
+/// satisfiable by loaders. See [`Builder::warn_risky_framebuffer_request`].
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FramebufferRequestWarning {
+    /// The framebuffer is [`HeaderTagFlag::Required`] but has a depth that
+    /// loaders rarely provide.
+    #[display(fmt = "Required framebuffer depth of {} bpp is rarely supported", _0)]
+    UncommonDepth(u32),
+}
+
+/// Errors of [`Builder::try_build`] about inconsistent tags.
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BuilderError {
+    /// An [`HeaderTagISA::I386`] header contains no tags besides the
+    /// [`EndHeaderTag`].
+    #[display(fmt = "The header contains no tags")]
+    Empty,
+    /// The [`InformationRequestHeaderTag`] contains no requests.
+    #[display(fmt = "The information request tag contains no requests")]
+    EmptyInformationRequest,
+    /// The minimum address of the [`RelocatableHeaderTag`] is bigger than
+    /// its maximum address.
+    #[display(fmt = "Invalid relocatable range {:#x}..={:#x}", min_addr, max_addr)]
+    InvalidRelocatableRange {
+        /// The minimum address.
+        min_addr: u32,
+        /// The maximum address.
+        max_addr: u32,
+    },
+    /// A tag type that may appear at most once is present multiple times.
+    /// This can only happen via [`Builder::vendor_tag`], as the dedicated
+    /// setters replace a previously set tag.
+    #[display(fmt = "Duplicate {} tag", _0)]
+    DuplicateTag(HeaderTagType),
+}
+
+#[cfg(feature = "unstable")]
+impl core::error::Error for BuilderError {}
+
+/// Error of [`Builder::build_into`].
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BuildIntoError {
+    /// The buffer is too small for the header.
+    #[display(fmt = "The buffer is too small, {} bytes are needed", needed)]
+    TooSmall {
+        /// The size of the header in bytes.
+        needed: usize,
+    },
+}
+
+#[cfg(feature = "unstable")]
+impl core::error::Error for BuildIntoError {}
 
 /// Builder for a Multiboot2 header information.
 #[derive(Debug)]
@@ -23,7 +101,7 @@ pub struct Builder {
     efi_32_tag: Option<EntryEfi32HeaderTag>,
     efi_64_tag: Option<EntryEfi64HeaderTag>,
     relocatable_tag: Option<RelocatableHeaderTag>,
-    // TODO add support for custom tags once someone requests it.
+    vendor_tags: Vec<Box<DynSizedStructure<HeaderTagHeader>>>,
 }
 
 impl Builder {
@@ -42,9 +120,37 @@ impl Builder {
             efi_32_tag: None,
             efi_64_tag: None,
             relocatable_tag: None,
+            vendor_tags: Vec::new(),
         }
     }
 
+    /// Creates a builder for a payload that boots via legacy BIOS and UEFI.
+    /// It contains an [`EntryAddressHeaderTag`] with `bios_entry`, and an
+    /// [`EntryEfi64HeaderTag`] with `efi64_entry` along with an
+    /// [`EfiBootServiceHeaderTag`]. The EFI tags are
+    /// [`HeaderTagFlag::Optional`], so that BIOS loaders may ignore them.
+    ///
+    /// This returns a builder rather than the bytes of the header, so that
+    /// further tags, such as an [`InformationRequestHeaderTag`], can be added
+    /// before [`Self::build`] or [`Self::build_into`] is called.
+    ///
+    /// Fails if `efi64_entry` doesn't fit into the 32-bit field of the tag,
+    /// instead of truncating the address.
+    pub fn dual_boot(
+        arch: HeaderTagISA,
+        bios_entry: u32,
+        efi64_entry: u64,
+    ) -> Result<Self, AddressOverflowError> {
+        let efi_64_tag = EntryEfi64HeaderTag::new_u64(HeaderTagFlag::Optional, efi64_entry)?;
+        Ok(Self::new(arch)
+            .entry_tag(EntryAddressHeaderTag::new(
+                HeaderTagFlag::Required,
+                bios_entry,
+            ))
+            .efi_bs_tag(EfiBootServiceHeaderTag::new(HeaderTagFlag::Optional))
+            .efi_64_tag(efi_64_tag))
+    }
+
     /// Set the [`InformationRequestHeaderTag`] tag.
     #[must_use]
     pub fn information_request_tag(
@@ -55,6 +161,101 @@ impl Builder {
         self
     }
 
+    /// Adds the conventional tags of the given [`Preset`]. Tags that are
+    /// already set and part of the preset are replaced.
+    #[must_use]
+    pub const fn preset(self, preset: Preset) -> Self {
+        let this = self.module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required));
+        match preset {
+            Preset::BiosElf => this,
+            Preset::UefiRelocatable => this
+                .efi_bs_tag(EfiBootServiceHeaderTag::new(HeaderTagFlag::Required))
+                .relocatable_tag(RelocatableHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    0x10_0000,
+                    u32::MAX,
+                    4096,
+                    RelocatableHeaderTagPreference::None,
+                )),
+            Preset::LegacyText => this.console_tag(ConsoleHeaderTag::new(
+                HeaderTagFlag::Required,
+                ConsoleHeaderTagFlags::EgaTextSupported,
+            )),
+        }
+    }
+
+    /// Merges the requests of `other` into the [`InformationRequestHeaderTag`]
+    /// of the builder. Requests that are already present are not added again.
+    /// If no information request tag is set yet, a copy of `other` is used.
+    /// The merged tag is [`HeaderTagFlag::Required`] if any of both tags is.
+    #[must_use]
+    pub fn merge_info_requests(mut self, other: &InformationRequestHeaderTag) -> Self {
+        let (flags, mut requests) = self.information_request_tag.take().map_or_else(
+            || (other.flags(), Vec::new()),
+            |tag| {
+                let flags = if tag.flags() == HeaderTagFlag::Required {
+                    HeaderTagFlag::Required
+                } else {
+                    other.flags()
+                };
+                (flags, tag.requests().to_vec())
+            },
+        );
+        for request in other.requests() {
+            if !requests.contains(request) {
+                requests.push(*request);
+            }
+        }
+        self.information_request_tag = Some(InformationRequestHeaderTag::new(flags, &requests));
+        self
+    }
+
+    /// Adds a request for the MBI tag type `typ` to the
+    /// [`InformationRequestHeaderTag`]. This is convenient for adding
+    /// requests conditionally in a loop. See [`Self::add_info_request_ids`].
+    #[must_use]
+    pub fn add_info_request(self, typ: MbiTagType) -> Self {
+        self.add_info_request_ids(&[typ.into()])
+    }
+
+    /// Adds the raw `requests` to the [`InformationRequestHeaderTag`].
+    /// Requests that are already present are not added again. The flags of
+    /// an existing tag are kept. If no information request tag is set yet, a
+    /// [`HeaderTagFlag::Required`] tag is created.
+    #[must_use]
+    pub fn add_info_request_ids(mut self, requests: &[MbiTagTypeId]) -> Self {
+        let (flags, mut all_requests) = self.information_request_tag.take().map_or_else(
+            || (HeaderTagFlag::Required, Vec::new()),
+            |tag| (tag.flags(), tag.requests().to_vec()),
+        );
+        for request in requests {
+            if !all_requests.contains(request) {
+                all_requests.push(*request);
+            }
+        }
+        self.information_request_tag = Some(InformationRequestHeaderTag::new(flags, &all_requests));
+        self
+    }
+
+    /// Adds the tags that are typically needed for a graphical boot with
+    /// GRUB2: A [`FramebufferHeaderTag`] with the preferred mode and a request
+    /// for the framebuffer boot information tag, which is merged into the
+    /// [`InformationRequestHeaderTag`]. Both are [`HeaderTagFlag::Optional`],
+    /// so that loaders without graphics support still boot the payload.
+    #[must_use]
+    pub fn grub_graphical(self, width: u32, height: u32, depth: u32) -> Self {
+        self.framebuffer_tag(FramebufferHeaderTag::new(
+            HeaderTagFlag::Optional,
+            width,
+            height,
+            depth,
+        ))
+        .merge_info_requests(&InformationRequestHeaderTag::new(
+            HeaderTagFlag::Optional,
+            &[MbiTagType::Framebuffer.into()],
+        ))
+    }
+
     /// Set the [`AddressHeaderTag`] tag.
     #[must_use]
     pub const fn address_tag(mut self, address_tag: AddressHeaderTag) -> Self {
@@ -118,45 +319,241 @@ impl Builder {
         self
     }
 
+    /// Adds a tag of the raw type `typ` with arbitrary `payload`. This is
+    /// useful for vendor-specific or experimental tags that are unknown to
+    /// this crate. Vendor tags are placed after all other tags, in the order
+    /// they are added. Padding is added automatically. Tags of the end type
+    /// are ignored, see [`Self::build`].
+    #[must_use]
+    pub fn vendor_tag(mut self, typ: u16, flags: HeaderTagFlag, payload: &[u8]) -> Self {
+        // The size is updated by `new_boxed`.
+        let header = HeaderTagHeader::new_raw(typ, flags, 0);
+        self.vendor_tags.push(new_boxed(header, &[payload]));
+        self
+    }
+
+    /// Checks the [`FramebufferHeaderTag`] for requests that are valid but
+    /// risky, as loaders may fail to satisfy them. The returned warnings
+    /// don't prevent building the header.
+    ///
+    /// A required framebuffer with all-zero dimensions is fine, as it means
+    /// that any graphics mode is acceptable.
+    #[must_use]
+    pub fn warn_risky_framebuffer_request(&self) -> Vec<FramebufferRequestWarning> {
+        let mut warnings = Vec::new();
+        if let Some(tag) = self.framebuffer_tag {
+            if tag.flags() == HeaderTagFlag::Required
+                && !tag.is_depth_supported(&COMMON_FRAMEBUFFER_DEPTHS)
+            {
+                warnings.push(FramebufferRequestWarning::UncommonDepth(tag.depth()));
+            }
+        }
+        warnings
+    }
+
     /// Returns properly aligned bytes on the heap representing a valid
-    /// Multiboot2 header structure.
+    /// Multiboot2 header structure. Padding bytes are zeroed.
+    ///
+    /// The caller never adds the [`EndHeaderTag`]: Exactly one is appended
+    /// automatically as the final tag. Vendor tags of the end type (see
+    /// [`Self::vendor_tag`]) are stripped, so that the header never contains
+    /// multiple terminators.
     #[must_use]
     pub fn build(self) -> Box<DynSizedStructure<Multiboot2BasicHeader>> {
         let header = Multiboot2BasicHeader::new(self.arch, 0);
         let mut byte_refs = Vec::new();
+        self.for_each_tag(|bytes| byte_refs.push(bytes));
+        byte_refs.push(&EndHeaderTag::BYTES);
+        new_boxed(header, byte_refs.as_slice())
+    }
+
+    /// Like [`Self::build`], but writes the header into `buf` instead of
+    /// allocating a new buffer, e.g., into a static buffer of a bootloader.
+    /// Padding bytes are zeroed.
+    ///
+    /// Like the whole [`Builder`], this needs the `builder` feature and thus
+    /// `alloc`, as the builder keeps the information request tag and vendor
+    /// tags on the heap. Only writing the header doesn't allocate.
+    ///
+    /// Returns the number of bytes written or
+    /// [`BuildIntoError::TooSmall`] if `buf` can't hold the header.
+    pub fn build_into(self, buf: &mut [u8]) -> Result<usize, BuildIntoError> {
+        let mut needed = mem::size_of::<Multiboot2BasicHeader>() + EndHeaderTag::BYTES.len();
+        self.for_each_tag(|bytes| needed += bytes.len());
+        if buf.len() < needed {
+            return Err(BuildIntoError::TooSmall { needed });
+        }
+
+        let header = Multiboot2BasicHeader::new(self.arch, needed as u32);
+        let mut offset = 0;
+        let mut write = |bytes: &[u8]| {
+            buf[offset..offset + bytes.len()].copy_from_slice(bytes);
+            offset += bytes.len();
+        };
+        write(&header.header_magic().to_ne_bytes());
+        write(&header.arch_raw().to_ne_bytes());
+        write(&header.length().to_ne_bytes());
+        write(&header.checksum().to_ne_bytes());
+        self.for_each_tag(&mut write);
+        write(&EndHeaderTag::BYTES);
+        Ok(needed)
+    }
+
+    /// Calls `f` with the bytes of each tag and then with zeroed padding up to
+    /// the next tag, in the order in which they appear in the header. The
+    /// [`EndHeaderTag`] is not included.
+    fn for_each_tag<'a>(&'a self, mut f: impl FnMut(&'a [u8])) {
+        static PADDING: [u8; ALIGNMENT] = [0; ALIGNMENT];
+        // The padding of the tag structs is uninitialized and must not be
+        // copied.
+        let mut f = |bytes: &'a [u8]| {
+            let size = u32::from_ne_bytes(bytes[4..8].try_into().unwrap()) as usize;
+            f(&bytes[..size]);
+            f(&PADDING[..bytes.len() - size]);
+        };
         if let Some(tag) = self.information_request_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            f(*tag.as_bytes());
         }
         if let Some(tag) = self.address_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            f(*tag.as_bytes());
         }
         if let Some(tag) = self.entry_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            f(*tag.as_bytes());
         }
         if let Some(tag) = self.console_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            f(*tag.as_bytes());
         }
         if let Some(tag) = self.framebuffer_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            f(*tag.as_bytes());
         }
         if let Some(tag) = self.module_align_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            f(*tag.as_bytes());
         }
         if let Some(tag) = self.efi_bs_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            f(*tag.as_bytes());
         }
         if let Some(tag) = self.efi_32_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            f(*tag.as_bytes());
         }
         if let Some(tag) = self.efi_64_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            f(*tag.as_bytes());
         }
         if let Some(tag) = self.relocatable_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            f(*tag.as_bytes());
         }
-        // TODO add support for custom tags once someone requests it.
-        new_boxed(header, byte_refs.as_slice())
+        for tag in self
+            .vendor_tags
+            .iter()
+            .filter(|tag| tag.header().typ() != Ok(HeaderTagType::End))
+        {
+            f(*tag.as_bytes());
+        }
+    }
+
+    /// Like [`Self::build`], but first validates that the tags are consistent.
+    /// Generators that build headers from user configuration get a typed
+    /// error instead of a malformed header. See [`BuilderError`].
+    pub fn try_build(self) -> Result<Box<DynSizedStructure<Multiboot2BasicHeader>>, BuilderError> {
+        let empty = self.information_request_tag.is_none()
+            && self.address_tag.is_none()
+            && self.entry_tag.is_none()
+            && self.console_tag.is_none()
+            && self.framebuffer_tag.is_none()
+            && self.module_align_tag.is_none()
+            && self.efi_bs_tag.is_none()
+            && self.efi_32_tag.is_none()
+            && self.efi_64_tag.is_none()
+            && self.relocatable_tag.is_none()
+            && self.vendor_tags.is_empty();
+        if self.arch == HeaderTagISA::I386 && empty {
+            return Err(BuilderError::Empty);
+        }
+        if let Some(tag) = self.information_request_tag.as_ref() {
+            if tag.requests().is_empty() {
+                return Err(BuilderError::EmptyInformationRequest);
+            }
+        }
+        if let Some(tag) = self.relocatable_tag.as_ref() {
+            if tag.min_addr() > tag.max_addr() {
+                return Err(BuilderError::InvalidRelocatableRange {
+                    min_addr: tag.min_addr(),
+                    max_addr: tag.max_addr(),
+                });
+            }
+        }
+        for typ in [
+            HeaderTagType::Relocatable,
+            HeaderTagType::Address,
+            HeaderTagType::EntryAddress,
+        ] {
+            let already_set = match typ {
+                HeaderTagType::Relocatable => self.relocatable_tag.is_some(),
+                HeaderTagType::Address => self.address_tag.is_some(),
+                _ => self.entry_tag.is_some(),
+            };
+            let vendor_count = self
+                .vendor_tags
+                .iter()
+                .filter(|tag| tag.header().typ() == Ok(typ))
+                .count();
+            if usize::from(already_set) + vendor_count > 1 {
+                return Err(BuilderError::DuplicateTag(typ));
+            }
+        }
+        Ok(self.build())
     }
+
+    /// Like [`Self::build`], but also returns a fast non-cryptographic digest
+    /// (64-bit FNV-1a) of the header. Build systems can use it to skip
+    /// re-embedding unchanged headers.
+    ///
+    /// The digest covers all bytes of the built header.
+    #[must_use]
+    pub fn build_with_digest(self) -> (Box<DynSizedStructure<Multiboot2BasicHeader>>, u64) {
+        let structure = self.build();
+        let digest = structure
+            .as_bytes()
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |digest, &byte| {
+                (digest ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+            });
+        (structure, digest)
+    }
+
+    /// Like [`Self::build`], but returns the bytes of the header followed by
+    /// the `sentinel` in native endianness. The sentinel is not covered by
+    /// the length of the header. This helps to find the end of the header in
+    /// a memory dump, for example, while debugging.
+    ///
+    /// The sentinel is not part of the Multiboot2 spec.
+    #[must_use]
+    pub fn build_with_sentinel(self, sentinel: u32) -> Vec<u8> {
+        let structure = self.build();
+        let mut bytes = structure.as_bytes().to_vec();
+        bytes.extend_from_slice(&sentinel.to_ne_bytes());
+        bytes
+    }
+
+    /// Like [`Self::build`], but immediately parses the result with
+    /// [`Multiboot2Header::load`]. This catches output that can't be parsed,
+    /// e.g., because of malformed vendor tags.
+    pub fn build_and_verify(
+        self,
+    ) -> Result<Box<DynSizedStructure<Multiboot2BasicHeader>>, LoadError> {
+        let structure = self.build();
+        // SAFETY: The structure is properly aligned and covers the whole
+        // header.
+        unsafe { Multiboot2Header::load(structure.as_bytes().as_ptr().cast()) }?;
+        Ok(structure)
+    }
+}
+
+/// Returns the number of padding bytes that the builder inserts after a tag
+/// of `prev_size` bytes. Each tag starts at an 8-byte aligned offset, as
+/// demanded by the spec.
+#[must_use]
+pub const fn padding_between(prev_size: u32) -> usize {
+    increase_to_alignment(prev_size as usize) - prev_size as usize
 }
 
 #[cfg(test)]
@@ -165,7 +562,10 @@ mod tests {
     use crate::ConsoleHeaderTagFlags::ConsoleRequired;
     use crate::HeaderTagFlag::{Optional, Required};
     use crate::RelocatableHeaderTagPreference::High;
-    use crate::{MbiTagType, Multiboot2Header};
+
+    fn load(structure: &DynSizedStructure<Multiboot2BasicHeader>) -> Multiboot2Header<'_> {
+        unsafe { Multiboot2Header::load(structure.as_bytes().as_ptr().cast()) }.unwrap()
+    }
 
     #[test]
     fn build_and_parse() {
@@ -212,9 +612,7 @@ mod tests {
             ));
 
         let structure = builder.build();
-        let header =
-            unsafe { Multiboot2Header::load(structure.as_bytes().as_ref().as_ptr().cast()) }
-                .unwrap();
+        let header = load(&structure);
 
         assert!(header.verify_checksum());
 
@@ -222,7 +620,7 @@ mod tests {
             dbg!(tag);
         }
 
-        dbg!(header.arch());
+        assert_eq!(header.arch(), Ok(HeaderTagISA::I386));
         dbg!(header.checksum());
         dbg!(header.information_request_tag());
         dbg!(header.address_tag());
@@ -235,4 +633,536 @@ mod tests {
         dbg!(header.entry_address_efi64_tag());
         dbg!(header.relocatable_tag());
     }
+
+    #[test]
+    fn merge_info_requests() {
+        let structure = Builder::new(HeaderTagISA::I386)
+            .information_request_tag(InformationRequestHeaderTag::new(
+                Optional,
+                &[MbiTagType::Cmdline.into(), MbiTagType::Mmap.into()],
+            ))
+            .merge_info_requests(&InformationRequestHeaderTag::new(
+                Required,
+                &[MbiTagType::Mmap.into(), MbiTagType::Framebuffer.into()],
+            ))
+            .build();
+        let header = load(&structure);
+
+        let tag = header.information_request_tag().unwrap();
+        let expected: [MbiTagTypeId; 3] = [
+            MbiTagType::Cmdline.into(),
+            MbiTagType::Mmap.into(),
+            MbiTagType::Framebuffer.into(),
+        ];
+        assert_eq!(tag.flags(), Required);
+        assert_eq!(tag.requests(), expected);
+    }
+
+    #[test]
+    fn presets() {
+        let structure = Builder::new(HeaderTagISA::I386)
+            .preset(Preset::BiosElf)
+            .build();
+        let header = load(&structure);
+        let tags = header
+            .iter()
+            .map(|tag| tag.header().typ().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(tags, [HeaderTagType::ModuleAlign, HeaderTagType::End]);
+
+        let structure = Builder::new(HeaderTagISA::I386)
+            .preset(Preset::UefiRelocatable)
+            .build();
+        let header = load(&structure);
+        let tags = header
+            .iter()
+            .map(|tag| tag.header().typ().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tags,
+            [
+                HeaderTagType::ModuleAlign,
+                HeaderTagType::EfiBS,
+                HeaderTagType::Relocatable,
+                HeaderTagType::End
+            ]
+        );
+
+        let structure = Builder::new(HeaderTagISA::I386)
+            .preset(Preset::LegacyText)
+            .build();
+        let header = load(&structure);
+        let tags = header
+            .iter()
+            .map(|tag| tag.header().typ().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tags,
+            [
+                HeaderTagType::ConsoleFlags,
+                HeaderTagType::ModuleAlign,
+                HeaderTagType::End
+            ]
+        );
+        assert_eq!(
+            header.console_flags_tag().unwrap().console_flags(),
+            ConsoleHeaderTagFlags::EgaTextSupported
+        );
+    }
+
+    #[test]
+    fn test_padding_between() {
+        assert_eq!(padding_between(8), 0);
+        assert_eq!(padding_between(12), 4);
+        assert_eq!(padding_between(9), 7);
+        assert_eq!(padding_between(16), 0);
+
+        // The console tag is 12 bytes in size and followed by 4 padding bytes.
+        let console_tag = ConsoleHeaderTag::new(Required, ConsoleRequired);
+        let structure = Builder::new(HeaderTagISA::I386)
+            .console_tag(console_tag)
+            .module_align_tag(ModuleAlignHeaderTag::new(Required))
+            .build();
+        let bytes = structure.as_bytes();
+        let next_tag = 16 + console_tag.size() as usize + padding_between(console_tag.size());
+        assert_eq!(bytes[next_tag], HeaderTagType::ModuleAlign as u8);
+    }
+
+    #[test]
+    fn dual_boot() {
+        let structure = Builder::dual_boot(HeaderTagISA::I386, 0x10_0000, 0x20_0000)
+            .unwrap()
+            .build();
+        let header = load(&structure);
+        assert_eq!(header.entry_address_tag().unwrap().entry_addr(), 0x10_0000);
+        assert_eq!(
+            header.entry_address_efi64_tag().unwrap().entry_addr(),
+            0x20_0000
+        );
+        assert!(header.efi_boot_services_tag().is_some());
+
+        let err = Builder::dual_boot(HeaderTagISA::I386, 0x10_0000, 0x1_0000_0000);
+        assert_eq!(err.unwrap_err(), AddressOverflowError(0x1_0000_0000));
+    }
+
+    #[test]
+    fn vendor_tag() {
+        let structure = Builder::new(HeaderTagISA::I386)
+            .module_align_tag(ModuleAlignHeaderTag::new(Required))
+            .vendor_tag(0x1337, Required, &[1, 2, 3])
+            .vendor_tag(0x1338, Optional, &[])
+            .build();
+        let header = load(&structure);
+        assert_eq!(header.length(), 16 + 8 + 16 + 8 + 8);
+
+        let unsupported = header
+            .unsupported_required_tags(&[HeaderTagType::ModuleAlign])
+            .collect::<Vec<_>>();
+        assert_eq!(unsupported.len(), 1);
+        assert_eq!(unsupported[0].typ_raw(), 0x1337);
+        assert_eq!(unsupported[0].size(), 8 + 3);
+        assert_eq!(
+            header.unknown_tag_types().collect::<Vec<_>>(),
+            [0x1337, 0x1338]
+        );
+    }
+
+    #[test]
+    fn warn_risky_framebuffer_request() {
+        let builder = Builder::new(HeaderTagISA::I386)
+            .framebuffer_tag(FramebufferHeaderTag::new(Required, 1024, 768, 48));
+        assert_eq!(
+            builder.warn_risky_framebuffer_request(),
+            [FramebufferRequestWarning::UncommonDepth(48)]
+        );
+
+        let builder = Builder::new(HeaderTagISA::I386)
+            .framebuffer_tag(FramebufferHeaderTag::new(Optional, 1024, 768, 48));
+        assert!(builder.warn_risky_framebuffer_request().is_empty());
+
+        let builder = Builder::new(HeaderTagISA::I386)
+            .framebuffer_tag(FramebufferHeaderTag::new(Required, 0, 0, 0));
+        assert!(builder.warn_risky_framebuffer_request().is_empty());
+
+        let builder = Builder::new(HeaderTagISA::I386)
+            .framebuffer_tag(FramebufferHeaderTag::new(Required, 1024, 768, 32));
+        assert!(builder.warn_risky_framebuffer_request().is_empty());
+    }
+
+    #[test]
+    fn build_with_digest() {
+        let builder = || {
+            Builder::new(HeaderTagISA::I386)
+                .console_tag(ConsoleHeaderTag::new(Required, ConsoleRequired))
+                .relocatable_tag(RelocatableHeaderTag::new(
+                    Required, 0x9000, 0x10000, 4096, High,
+                ))
+        };
+        let (structure, digest) = builder().build_with_digest();
+        assert_eq!(structure.as_bytes().len(), 16 + 16 + 24 + 8);
+        // FNV-1a of the empty input is its offset basis.
+        let fnv1a = |bytes: &[u8]| {
+            bytes
+                .iter()
+                .fold(0xcbf2_9ce4_8422_2325_u64, |digest, &byte| {
+                    (digest ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+                })
+        };
+        assert_eq!(fnv1a(&[]), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(digest, fnv1a(&structure.as_bytes()));
+        assert_eq!(builder().build_with_digest().1, digest);
+
+        let (_, other_digest) = builder()
+            .relocatable_tag(RelocatableHeaderTag::new(
+                Required, 0x9000, 0x20000, 4096, High,
+            ))
+            .build_with_digest();
+        assert_ne!(other_digest, digest);
+    }
+
+    #[test]
+    fn add_info_request() {
+        let builder = [MbiTagType::Cmdline, MbiTagType::Mmap, MbiTagType::Cmdline]
+            .into_iter()
+            .fold(Builder::new(HeaderTagISA::I386), Builder::add_info_request);
+        let builder = builder
+            .add_info_request_ids(&[MbiTagType::Framebuffer.into(), MbiTagTypeId::from(0x1337)]);
+        let tag = builder.information_request_tag.as_ref().unwrap();
+        assert_eq!(tag.flags(), Required);
+        let expected: [MbiTagTypeId; 4] = [
+            MbiTagType::Cmdline.into(),
+            MbiTagType::Mmap.into(),
+            MbiTagType::Framebuffer.into(),
+            MbiTagTypeId::from(0x1337),
+        ];
+        assert_eq!(tag.requests(), expected);
+
+        // The flags of an existing tag are kept.
+        let builder = Builder::new(HeaderTagISA::I386)
+            .information_request_tag(InformationRequestHeaderTag::new(
+                Optional,
+                &[MbiTagType::Cmdline.into()],
+            ))
+            .add_info_request(MbiTagType::Mmap);
+        let tag = builder.information_request_tag.as_ref().unwrap();
+        assert_eq!(tag.flags(), Optional);
+        let expected: [MbiTagTypeId; 2] = [MbiTagType::Cmdline.into(), MbiTagType::Mmap.into()];
+        assert_eq!(tag.requests(), expected);
+    }
+
+    #[test]
+    fn build_with_sentinel() {
+        let bytes = Builder::new(HeaderTagISA::I386)
+            .console_tag(ConsoleHeaderTag::new(Required, ConsoleRequired))
+            .build_with_sentinel(0xdead_beef);
+        // The vector is not necessarily aligned, so the header can't be loaded.
+        let length = u32::from_ne_bytes(bytes[8..12].try_into().unwrap()) as usize;
+        assert_eq!(length, 16 + 16 + 8);
+        assert_eq!(bytes.len(), length + 4);
+        assert_eq!(&bytes[length - 8..length], &EndHeaderTag::BYTES);
+        assert_eq!(&bytes[length..], &0xdead_beef_u32.to_ne_bytes());
+    }
+
+    #[test]
+    fn end_tag() {
+        let structure = Builder::new(HeaderTagISA::I386)
+            .relocatable_tag(RelocatableHeaderTag::new(
+                Required, 0x9000, 0x10000, 4096, High,
+            ))
+            .build();
+        let header = load(&structure);
+        let last_tag = header.iter().last().unwrap();
+        assert_eq!(last_tag.header().typ(), Ok(HeaderTagType::End));
+        assert_eq!(last_tag.header().size(), 8);
+    }
+
+    #[test]
+    fn efi_bs_tag() {
+        let structure = Builder::new(HeaderTagISA::I386)
+            .efi_bs_tag(EfiBootServiceHeaderTag::new(Required))
+            .build();
+        let header = load(&structure);
+        assert!(header.verify_checksum());
+        assert_eq!(header.length(), 16 + 8 + 8);
+        assert_eq!(
+            header.efi_boot_services_tag(),
+            Some(&EfiBootServiceHeaderTag::new(Required))
+        );
+    }
+
+    #[test]
+    fn console_tag() {
+        let structure = Builder::new(HeaderTagISA::I386)
+            .console_tag(ConsoleHeaderTag::new(
+                Required,
+                ConsoleHeaderTagFlags::EgaTextSupported,
+            ))
+            .build();
+        let header = load(&structure);
+        // The 12-byte tag is padded to 16 bytes.
+        assert_eq!(header.length(), 16 + 16 + 8);
+        let tag = header.console_flags_tag().unwrap();
+        assert_eq!(tag.typ(), HeaderTagType::ConsoleFlags);
+        assert_eq!(tag.flags(), Required);
+        assert_eq!(tag.console_flags(), ConsoleHeaderTagFlags::EgaTextSupported);
+    }
+
+    #[test]
+    fn grub_graphical() {
+        let structure = Builder::new(HeaderTagISA::I386)
+            .information_request_tag(InformationRequestHeaderTag::new(
+                Required,
+                &[MbiTagType::Cmdline.into()],
+            ))
+            .grub_graphical(1024, 768, 32)
+            .build();
+        let header = load(&structure);
+        assert_eq!(
+            header.framebuffer_tag(),
+            Some(&FramebufferHeaderTag::new(Optional, 1024, 768, 32))
+        );
+        let irs = header.information_request_tag().unwrap();
+        assert_eq!(irs.flags(), Required);
+        assert_eq!(
+            irs.requests(),
+            &[
+                MbiTagTypeId::from(MbiTagType::Cmdline),
+                MbiTagTypeId::from(MbiTagType::Framebuffer)
+            ]
+        );
+    }
+
+    #[test]
+    fn module_align_tag() {
+        let structure = Builder::new(HeaderTagISA::I386)
+            .module_align_tag(ModuleAlignHeaderTag::new(Required))
+            .build();
+        let header = load(&structure);
+        assert_eq!(header.length(), 16 + 8 + 8);
+        assert_eq!(
+            header.module_align_tag(),
+            Some(&ModuleAlignHeaderTag::new(Required))
+        );
+        assert!(header.iter().any(|tag| {
+            tag.header().typ() == Ok(HeaderTagType::ModuleAlign) && tag.header().size() == 8
+        }));
+    }
+
+    #[test]
+    fn address_tag() {
+        let structure = Builder::new(HeaderTagISA::I386)
+            .address_tag(AddressHeaderTag::new(
+                Required, 0x1000, 0x2000, 0x3000, 0x4000,
+            ))
+            .build();
+        let bytes = structure.as_bytes();
+        #[rustfmt::skip]
+        let expected: [u8; 24] = [
+            /* type, flags, size */
+            2, 0, 0, 0,
+            24, 0, 0, 0,
+            /* header_addr */
+            0x00, 0x10, 0, 0,
+            /* load_addr */
+            0x00, 0x20, 0, 0,
+            /* load_end_addr */
+            0x00, 0x30, 0, 0,
+            /* bss_end_addr */
+            0x00, 0x40, 0, 0,
+        ];
+        assert_eq!(&bytes[16..40], &expected);
+
+        let header = load(&structure);
+        let tag = header.address_tag().unwrap();
+        assert_eq!(tag.header_addr(), 0x1000);
+        assert_eq!(tag.load_addr(), 0x2000);
+        assert_eq!(tag.load_end_addr(), 0x3000);
+        assert_eq!(tag.bss_end_addr(), 0x4000);
+    }
+
+    #[test]
+    fn entry_tags() {
+        let structure = Builder::new(HeaderTagISA::I386)
+            .entry_tag(EntryAddressHeaderTag::new(Required, 0x5000))
+            .efi_32_tag(EntryEfi32HeaderTag::new(Optional, 0x6000))
+            .efi_64_tag(EntryEfi64HeaderTag::new(Optional, 0x7000))
+            .build();
+        let header = load(&structure);
+        assert!(header.verify_checksum());
+        // Each 12-byte tag is padded to 16 bytes.
+        assert_eq!(header.length(), 16 + 3 * 16 + 8);
+        assert_eq!(header.entry_address_tag().unwrap().entry_addr(), 0x5000);
+        assert_eq!(
+            header.entry_address_efi32_tag().unwrap().entry_addr(),
+            0x6000
+        );
+        assert_eq!(
+            header.entry_address_efi64_tag().unwrap().entry_addr(),
+            0x7000
+        );
+        let last_tag = header.iter().last().unwrap();
+        assert_eq!(last_tag.header().typ(), Ok(HeaderTagType::End));
+    }
+
+    #[test]
+    fn build_and_verify() {
+        let builder = || {
+            Builder::new(HeaderTagISA::I386)
+                .entry_tag(EntryAddressHeaderTag::new(Required, 0x5000))
+                .relocatable_tag(RelocatableHeaderTag::new(
+                    Required, 0x9000, 0x10000, 4096, High,
+                ))
+        };
+        let structure = builder().build_and_verify().unwrap();
+        assert_eq!(structure.as_bytes().len(), 16 + 16 + 24 + 8);
+
+        // A second entry address tag makes the header ambiguous.
+        let result = builder()
+            .vendor_tag(
+                HeaderTagType::EntryAddress as u16,
+                Required,
+                &0x6000_u32.to_le_bytes(),
+            )
+            .build_and_verify();
+        assert_eq!(result.unwrap_err(), LoadError::DuplicateEntryAddress);
+    }
+
+    #[test]
+    fn try_build() {
+        let structure = Builder::new(HeaderTagISA::I386)
+            .module_align_tag(ModuleAlignHeaderTag::new(Required))
+            .try_build()
+            .unwrap();
+        assert_eq!(structure.as_bytes().len(), 16 + 8 + 8);
+
+        assert_eq!(
+            Builder::new(HeaderTagISA::I386).try_build().unwrap_err(),
+            BuilderError::Empty
+        );
+        assert!(Builder::new(HeaderTagISA::MIPS32).try_build().is_ok());
+        assert_eq!(
+            Builder::new(HeaderTagISA::I386)
+                .information_request_tag(InformationRequestHeaderTag::new(Required, &[]))
+                .try_build()
+                .unwrap_err(),
+            BuilderError::EmptyInformationRequest
+        );
+        assert_eq!(
+            Builder::new(HeaderTagISA::I386)
+                .relocatable_tag(RelocatableHeaderTag::new_unchecked(
+                    Required, 0x10000, 0x9000, 4096, High,
+                ))
+                .try_build()
+                .unwrap_err(),
+            BuilderError::InvalidRelocatableRange {
+                min_addr: 0x10000,
+                max_addr: 0x9000
+            }
+        );
+    }
+
+    #[test]
+    fn try_build_duplicate_tags() {
+        let builder = || {
+            Builder::new(HeaderTagISA::I386)
+                .relocatable_tag(RelocatableHeaderTag::new(
+                    Required, 0x9000, 0x10000, 4096, High,
+                ))
+                .address_tag(AddressHeaderTag::new(Required, 0x1000, 0x1000, 0, 0))
+                .entry_tag(EntryAddressHeaderTag::new(Required, 0x1000))
+        };
+        assert!(builder().try_build().is_ok());
+
+        for typ in [
+            HeaderTagType::Relocatable,
+            HeaderTagType::Address,
+            HeaderTagType::EntryAddress,
+        ] {
+            // Duplicate of a tag set via the dedicated setter.
+            assert_eq!(
+                builder()
+                    .vendor_tag(typ as u16, Required, &[0; 8])
+                    .try_build()
+                    .unwrap_err(),
+                BuilderError::DuplicateTag(typ)
+            );
+            // Duplicate among vendor tags.
+            assert_eq!(
+                Builder::new(HeaderTagISA::I386)
+                    .vendor_tag(typ as u16, Required, &[0; 8])
+                    .vendor_tag(typ as u16, Required, &[0; 8])
+                    .try_build()
+                    .unwrap_err(),
+                BuilderError::DuplicateTag(typ)
+            );
+        }
+
+        // Setting a tag twice replaces it.
+        assert!(builder()
+            .entry_tag(EntryAddressHeaderTag::new(Required, 0x2000))
+            .try_build()
+            .is_ok());
+    }
+
+    #[test]
+    fn single_end_tag() {
+        let structure = Builder::new(HeaderTagISA::I386)
+            .relocatable_tag(RelocatableHeaderTag::new(
+                Required, 0x9000, 0x10000, 4096, High,
+            ))
+            .vendor_tag(HeaderTagType::End as u16, Required, &[])
+            .build();
+        let bytes = structure.as_bytes();
+        assert_eq!(bytes.len(), 16 + 24 + 8);
+        assert_eq!(&bytes[bytes.len() - 8..], &EndHeaderTag::BYTES);
+
+        let header = load(&structure);
+        let end_tags = header
+            .iter()
+            .filter(|tag| tag.header().typ() == Ok(HeaderTagType::End))
+            .count();
+        assert_eq!(end_tags, 1);
+    }
+
+    #[test]
+    fn build_into() {
+        let builder = || {
+            Builder::new(HeaderTagISA::I386)
+                .console_tag(ConsoleHeaderTag::new(
+                    Required,
+                    ConsoleHeaderTagFlags::EgaTextSupported,
+                ))
+                .relocatable_tag(RelocatableHeaderTag::new(
+                    Required, 0x9000, 0x10000, 4096, High,
+                ))
+        };
+        let mut buf = multiboot2_common::test_utils::AlignedBytes([0xff_u8; 128]);
+        let len = builder().build_into(&mut buf.0).unwrap();
+        assert_eq!(len, 16 + 16 + 24 + 8);
+        // The padding behind the console tag is zeroed.
+        assert_eq!(&buf.0[28..32], &[0; 4]);
+        assert_eq!(buf.0[len], 0xff);
+
+        let header = unsafe { Multiboot2Header::load(buf.0.as_ptr().cast()) }.unwrap();
+        assert_eq!(header.length() as usize, len);
+        assert_eq!(
+            header.console_flags_tag(),
+            Some(&ConsoleHeaderTag::new(
+                Required,
+                ConsoleHeaderTagFlags::EgaTextSupported
+            ))
+        );
+        assert_eq!(
+            header.relocatable_tag(),
+            Some(&RelocatableHeaderTag::new(
+                Required, 0x9000, 0x10000, 4096, High,
+            ))
+        );
+
+        assert_eq!(
+            builder().build_into(&mut buf.0[..len - 1]),
+            Err(BuildIntoError::TooSmall { needed: len })
+        );
+    }
 }
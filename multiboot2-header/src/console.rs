@@ -36,7 +36,7 @@ impl ConsoleHeaderTag {
     /// Returns the [`HeaderTagType`].
     #[must_use]
     pub const fn typ(&self) -> HeaderTagType {
-        self.header.typ()
+        Self::ID
     }
 
     /// Returns the [`HeaderTagFlag`]s.
@@ -16,11 +16,22 @@ impl Default for EndHeaderTag {
 }
 
 impl EndHeaderTag {
+    /// The raw bytes of the end tag. This enables the assembly of a complete
+    /// header in a `const` context, e.g., in a static byte array.
+    pub const BYTES: [u8; 8] = {
+        let typ = (HeaderTagType::End as u16).to_ne_bytes();
+        let flags = (HeaderTagFlag::Required as u16).to_ne_bytes();
+        let size = (mem::size_of::<Self>() as u32).to_ne_bytes();
+        [
+            typ[0], typ[1], flags[0], flags[1], size[0], size[1], size[2], size[3],
+        ]
+    };
+
     /// Constructs a new tag.
     #[must_use]
     pub const fn new() -> Self {
         let header = HeaderTagHeader::new(
-            HeaderTagType::EntryAddress,
+            HeaderTagType::End,
             HeaderTagFlag::Required,
             mem::size_of::<Self>() as u32,
         );
@@ -30,7 +41,7 @@ impl EndHeaderTag {
     /// Returns the [`HeaderTagType`].
     #[must_use]
     pub const fn typ(&self) -> HeaderTagType {
-        self.header.typ()
+        Self::ID
     }
 
     /// Returns the [`HeaderTagFlag`]s.
@@ -61,10 +72,28 @@ impl Tag for EndHeaderTag {
 
 #[cfg(test)]
 mod tests {
-    use crate::EndHeaderTag;
+    use crate::{EndHeaderTag, HeaderTagFlag, HeaderTagType};
 
     #[test]
     fn test_assert_size() {
         assert_eq!(core::mem::size_of::<EndHeaderTag>(), 2 + 2 + 4);
     }
+
+    #[test]
+    fn test_new() {
+        let tag = EndHeaderTag::new();
+        assert_eq!(tag.typ(), HeaderTagType::End);
+        assert_eq!(tag.flags(), HeaderTagFlag::Required);
+        assert_eq!(tag.size(), 8);
+        #[cfg(target_endian = "little")]
+        assert_eq!(EndHeaderTag::BYTES, [0, 0, 0, 0, 8, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_bytes() {
+        const END: [u8; 8] = EndHeaderTag::BYTES;
+        assert_eq!(u16::from_ne_bytes([END[0], END[1]]), 0);
+        assert_eq!(u16::from_ne_bytes([END[2], END[3]]), 0);
+        assert_eq!(u32::from_ne_bytes([END[4], END[5], END[6], END[7]]), 8);
+    }
 }
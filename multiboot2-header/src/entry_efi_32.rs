@@ -1,4 +1,4 @@
-use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
+use crate::{AddressOverflowError, HeaderTagFlag, HeaderTagHeader, HeaderTagType};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use core::mem;
@@ -29,10 +29,18 @@ impl EntryEfi32HeaderTag {
         Self { header, entry_addr }
     }
 
+    /// Like [`Self::new`] but takes the entry address as `u64`, as it is
+    /// common on 64-bit hosts.
+    ///
+    /// Fails if the address doesn't fit into the 32-bit field of the tag.
+    pub fn new_u64(flags: HeaderTagFlag, entry_addr: u64) -> Result<Self, AddressOverflowError> {
+        Ok(Self::new(flags, AddressOverflowError::check(entry_addr)?))
+    }
+
     /// Returns the [`HeaderTagType`].
     #[must_use]
     pub const fn typ(&self) -> HeaderTagType {
-        self.header.typ()
+        Self::ID
     }
 
     /// Returns the [`HeaderTagFlag`]s.
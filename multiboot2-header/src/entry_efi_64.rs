@@ -1,4 +1,4 @@
-use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
+use crate::{AddressOverflowError, HeaderTagFlag, HeaderTagHeader, HeaderTagType};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use core::mem;
@@ -29,10 +29,31 @@ impl EntryEfi64HeaderTag {
         Self { header, entry_addr }
     }
 
+    /// Like [`Self::new`] but takes the entry address as `u64`, as it is
+    /// common on 64-bit hosts.
+    ///
+    /// Fails if the address doesn't fit into the 32-bit field of the tag.
+    pub fn new_u64(flags: HeaderTagFlag, entry_addr: u64) -> Result<Self, AddressOverflowError> {
+        Ok(Self::new(flags, AddressOverflowError::check(entry_addr)?))
+    }
+
+    /// Like [`Self::new_u64`] but takes the address of the entry function `f`.
+    /// This is convenient, for example, in tests that embed a real entry.
+    ///
+    /// Fails if the address of `f` doesn't fit into the 32-bit field of the
+    /// tag, which is common for position-independent host binaries.
+    #[cfg(target_pointer_width = "64")]
+    pub fn from_fn(
+        flags: HeaderTagFlag,
+        f: extern "C" fn() -> !,
+    ) -> Result<Self, AddressOverflowError> {
+        Self::new_u64(flags, f as usize as u64)
+    }
+
     /// Returns the [`HeaderTagType`].
     #[must_use]
     pub const fn typ(&self) -> HeaderTagType {
-        self.header.typ()
+        Self::ID
     }
 
     /// Returns the [`HeaderTagFlag`]s.
@@ -77,3 +98,37 @@ impl Tag for EntryEfi64HeaderTag {
     type IDType = HeaderTagType;
     const ID: HeaderTagType = HeaderTagType::EntryAddressEFI64;
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{AddressOverflowError, EntryEfi64HeaderTag, HeaderTagFlag};
+
+    #[test]
+    fn test_new_u64() {
+        let tag = EntryEfi64HeaderTag::new_u64(HeaderTagFlag::Required, 0x10_0000);
+        assert_eq!(
+            tag,
+            Ok(EntryEfi64HeaderTag::new(HeaderTagFlag::Required, 0x10_0000))
+        );
+        assert_eq!(
+            EntryEfi64HeaderTag::new_u64(HeaderTagFlag::Required, 0x1_0010_0000),
+            Err(AddressOverflowError(0x1_0010_0000))
+        );
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_from_fn() {
+        extern "C" fn entry() -> ! {
+            unreachable!()
+        }
+
+        // The address of `entry` is chosen by the linker and may or may not
+        // fit into 32 bits. Both cases are covered exactly by `test_new_u64`.
+        let entry: extern "C" fn() -> ! = entry;
+        assert_eq!(
+            EntryEfi64HeaderTag::from_fn(HeaderTagFlag::Required, entry),
+            EntryEfi64HeaderTag::new_u64(HeaderTagFlag::Required, entry as usize as u64)
+        );
+    }
+}
@@ -32,7 +32,7 @@ impl FramebufferHeaderTag {
     /// Returns the [`HeaderTagType`].
     #[must_use]
     pub const fn typ(&self) -> HeaderTagType {
-        self.header.typ()
+        Self::ID
     }
 
     /// Returns the [`HeaderTagFlag`]s.
@@ -64,8 +64,58 @@ impl FramebufferHeaderTag {
     pub const fn depth(&self) -> u32 {
         self.depth
     }
+
+    /// Checks that the requested dimensions are consistent. A width or height
+    /// of `0` means that the payload has no preference, but specifying only
+    /// one of both is contradictory.
+    pub const fn validate(&self) -> Result<(), FramebufferHeaderTagError> {
+        if (self.width == 0) != (self.height == 0) {
+            return Err(FramebufferHeaderTagError::PartialDimensions {
+                width: self.width,
+                height: self.height,
+            });
+        }
+        Ok(())
+    }
+
+    /// Estimates the memory footprint of the requested framebuffer in bytes,
+    /// i.e., `pitch * height` with tightly packed lines. Loaders with limited
+    /// memory can use this to reject oversized requests. Returns `None` if
+    /// width, height, or depth is `0`, i.e., unspecified.
+    #[must_use]
+    pub const fn estimated_fb_bytes(&self) -> Option<u64> {
+        if self.width == 0 || self.height == 0 || self.depth == 0 {
+            return None;
+        }
+        let bytes_per_pixel = (self.depth as u64 + 7) / 8;
+        Some(self.width as u64 * bytes_per_pixel * self.height as u64)
+    }
+
+    /// Checks whether the requested depth is in the list of bits per pixel
+    /// values that a loader supports. A depth of `0` means that the payload
+    /// has no preference, which is always supported.
+    #[must_use]
+    pub fn is_depth_supported(&self, supported: &[u8]) -> bool {
+        self.depth == 0 || supported.iter().any(|&bpp| u32::from(bpp) == self.depth)
+    }
 }
 
+/// Errors of [`FramebufferHeaderTag::validate`].
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FramebufferHeaderTagError {
+    /// Only one of width and height is specified.
+    #[display(fmt = "Partial framebuffer dimensions {}x{}", width, height)]
+    PartialDimensions {
+        /// The requested width.
+        width: u32,
+        /// The requested height.
+        height: u32,
+    },
+}
+
+#[cfg(feature = "unstable")]
+impl core::error::Error for FramebufferHeaderTagError {}
+
 impl MaybeDynSized for FramebufferHeaderTag {
     type Header = HeaderTagHeader;
 
@@ -78,3 +128,45 @@ impl Tag for FramebufferHeaderTag {
     type IDType = HeaderTagType;
     const ID: HeaderTagType = HeaderTagType::Framebuffer;
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{FramebufferHeaderTag, FramebufferHeaderTagError, HeaderTagFlag};
+
+    #[test]
+    fn test_validate() {
+        let tag = FramebufferHeaderTag::new(HeaderTagFlag::Required, 1024, 768, 0);
+        assert_eq!(tag.validate(), Ok(()));
+        let tag = FramebufferHeaderTag::new(HeaderTagFlag::Required, 0, 0, 0);
+        assert_eq!(tag.validate(), Ok(()));
+        let tag = FramebufferHeaderTag::new(HeaderTagFlag::Required, 1024, 0, 32);
+        assert_eq!(
+            tag.validate(),
+            Err(FramebufferHeaderTagError::PartialDimensions {
+                width: 1024,
+                height: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_estimated_fb_bytes() {
+        let tag = FramebufferHeaderTag::new(HeaderTagFlag::Required, 3840, 2160, 32);
+        assert_eq!(tag.estimated_fb_bytes(), Some(3840 * 2160 * 4));
+        let tag = FramebufferHeaderTag::new(HeaderTagFlag::Required, 1024, 768, 15);
+        assert_eq!(tag.estimated_fb_bytes(), Some(1024 * 768 * 2));
+        let tag = FramebufferHeaderTag::new(HeaderTagFlag::Required, 1024, 768, 0);
+        assert_eq!(tag.estimated_fb_bytes(), None);
+    }
+
+    #[test]
+    fn test_is_depth_supported() {
+        let supported = [16, 24, 32];
+        let tag = FramebufferHeaderTag::new(HeaderTagFlag::Optional, 1024, 768, 32);
+        assert!(tag.is_depth_supported(&supported));
+        let tag = FramebufferHeaderTag::new(HeaderTagFlag::Optional, 1024, 768, 8);
+        assert!(!tag.is_depth_supported(&supported));
+        let tag = FramebufferHeaderTag::new(HeaderTagFlag::Optional, 1024, 768, 0);
+        assert!(tag.is_depth_supported(&supported));
+    }
+}
@@ -1,15 +1,27 @@
 use crate::{
-    AddressHeaderTag, ConsoleHeaderTag, EfiBootServiceHeaderTag, EntryAddressHeaderTag,
-    EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag, HeaderTagHeader, HeaderTagISA,
-    HeaderTagType, InformationRequestHeaderTag, ModuleAlignHeaderTag, RelocatableHeaderTag,
-    TagIter,
+    AddressHeaderTag, BootContext, ConsoleHeaderTag, ConsoleHeaderTagFlags, DisplayPreference,
+    EfiBootServiceHeaderTag, EntryAddressHeaderTag, EntryEfi32HeaderTag, EntryEfi64HeaderTag,
+    EntryPoint, FramebufferHeaderTag, HeaderTagFlag, HeaderTagHeader, HeaderTagISA, HeaderTagType,
+    HeaderTagVisitor, InformationRequestHeaderTag, LoaderPlan, MbiTagType, MbiTagTypeId,
+    ModuleAlignHeaderTag, ParsedHeaderTag, RelocatableHeaderTag, TagIter, UnknownHeaderTagISA,
+    UnknownRelocatableHeaderTagPreference,
 };
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, string::String, vec::Vec};
 #[cfg(feature = "unstable")]
 use core::error::Error;
+#[cfg(feature = "alloc")]
+use core::fmt::Write;
 use core::fmt::{Debug, Formatter};
 use core::mem::size_of;
+use core::ops::Range;
+use core::ptr;
 use core::ptr::NonNull;
-use multiboot2_common::{DynSizedStructure, Header, MemoryError, Tag, ALIGNMENT};
+#[cfg(feature = "alloc")]
+use multiboot2_common::new_boxed;
+use multiboot2_common::{
+    increase_to_alignment, DynSizedStructure, Header, MaybeDynSized, MemoryError, Tag, ALIGNMENT,
+};
 
 /// Magic value for a [`Multiboot2Header`], as defined by the spec.
 pub const MAGIC: u32 = 0xe85250d6;
@@ -30,23 +42,61 @@ impl<'a> Multiboot2Header<'a> {
     /// This may be because:
     /// - `addr` is a null-pointer
     /// - `addr` isn't 8-byte aligned
+    /// - the length is zero
+    /// - the length is smaller than the [`Multiboot2BasicHeader`]
     /// - the magic value of the header is not present
     /// - the checksum field is invalid
+    /// - a tag is smaller than its header or exceeds the header
+    /// - a tag of a type with a fixed size has a different size
+    /// - the size of the [`InformationRequestHeaderTag`] is malformed
+    /// - there is more than one [`EntryAddressHeaderTag`]
+    ///
+    /// These are the same checks as in [`Self::first_error`].
     ///
     /// # Safety
     /// This function may produce undefined behaviour, if the provided `addr` is not a valid
     /// Multiboot2 header pointer.
     pub unsafe fn load(ptr: *const Multiboot2BasicHeader) -> Result<Self, LoadError> {
         let ptr = NonNull::new(ptr.cast_mut()).ok_or(LoadError::Memory(MemoryError::Null))?;
+        if ptr.as_ptr().align_offset(ALIGNMENT) != 0 {
+            return Err(LoadError::Memory(MemoryError::WrongAlignment));
+        }
+        let base = core::slice::from_raw_parts(
+            ptr.as_ptr().cast::<u8>(),
+            size_of::<Multiboot2BasicHeader>(),
+        );
+        let (_, length, _) = check_base(base)?;
+        // Check the tags before the length is trusted to construct any of them.
+        let bytes = core::slice::from_raw_parts(ptr.as_ptr().cast::<u8>(), length as usize);
+        check_tags(bytes, length).map_err(|(_, err)| err)?;
         let inner = DynSizedStructure::ref_from_ptr(ptr).map_err(LoadError::Memory)?;
-        let this = Self(inner);
+        Ok(Self(inner))
+    }
 
-        let header = this.0.header();
-        if header.header_magic != MAGIC {
-            return Err(LoadError::MagicNotFound);
-        }
-        if !header.verify_checksum() {
-            return Err(LoadError::ChecksumMismatch);
+    /// Like [`Self::load`], but safe, as the header is read from `bytes`
+    /// instead of a raw pointer. Additionally fails with
+    /// [`LoadError::UnknownArch`] if the architecture is no known
+    /// [`HeaderTagISA`], and if [`Self::load_warnings`] reports any deviation
+    /// from the spec that [`Self::load`] tolerates, such as an oversized end
+    /// tag. Use this for headers from untrusted sources, such as files, so
+    /// that no field of the header is trusted before it was validated.
+    ///
+    /// `bytes` must be 8-byte aligned and cover the whole header. Otherwise,
+    /// consider [`OwnedMultiboot2Header::from_vec`] (requires the `alloc`
+    /// feature).
+    pub fn load_checked(bytes: &'a [u8]) -> Result<Self, LoadError> {
+        let (_, length, _) = check_base(bytes)?;
+        let header = bytes
+            .get(..length as usize)
+            .ok_or(LoadError::Memory(MemoryError::InvalidReportedTotalSize))?;
+        // SAFETY: The header memory is fully covered by `bytes`.
+        let this = unsafe { Self::load(header.as_ptr().cast()) }?;
+        this.arch().map_err(LoadError::UnknownArch)?;
+        if let Some(warning) = this.load_warnings().next() {
+            return Err(match warning {
+                LoadWarning::OversizedEndTag { size } => LoadError::OversizedEndTag { size },
+                LoadWarning::DuplicateInfoRequest(typ) => LoadError::DuplicateInfoRequest(typ),
+            });
         }
         Ok(this)
     }
@@ -95,16 +145,621 @@ impl<'a> Multiboot2Header<'a> {
         )
         .try_into()
         .unwrap();
+        if header_length == 0 {
+            return Err(LoadError::ZeroLength);
+        }
         Ok(Some((
             &buffer[magic_index..magic_index + header_length],
             magic_index as u32,
         )))
     }
 
-    /// Returns a [`TagIter`].
+    /// Like [`Self::find_header`], but also finds headers that are not
+    /// 8-byte aligned in `buffer`. This is against the spec but happens in
+    /// practice. The header is searched at any offset within the first 32 KiB
+    /// of `buffer` and its bytes are copied into a properly aligned
+    /// [`OwnedMultiboot2Header`].
+    ///
+    /// Returns the offset of the header in `buffer` together with the copy or
+    /// `None`, if no complete header was found that passes the checks of
+    /// [`OwnedMultiboot2Header::from_vec`].
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn find_in_realigned(buffer: &[u8]) -> Option<(usize, OwnedMultiboot2Header)> {
+        // The spec demands that the header is in the first 32768 bytes.
+        let search_end = buffer.len().min(32768);
+        (0..search_end).find_map(|offset| {
+            // The magic value, the checksum, and the tags are checked on the
+            // unaligned bytes, so only a valid header is copied.
+            let owned = OwnedMultiboot2Header::copy_from(&buffer[offset..]).ok()?;
+            Some((offset, owned))
+        })
+    }
+
+    /// Scans `bytes` for a Multiboot2 header, as required by the spec: at
+    /// 8-byte aligned offsets within the first 32768 bytes. A candidate is only
+    /// accepted if it passes the checks of [`Self::first_error`], e.g., if its
+    /// checksum is valid and the whole header lies within `bytes`.
+    ///
+    /// Returns the offset of the header in `bytes` together with the parsed
+    /// header or `None`, if no valid header was found. `bytes` itself must be
+    /// 8-byte aligned, as the header is not copied. Otherwise, consider
+    /// [`Self::find_in_realigned`].
+    #[must_use]
+    pub fn find(bytes: &'a [u8]) -> Option<(usize, Self)> {
+        let search_end = bytes.len().min(32768);
+        (0..search_end).step_by(ALIGNMENT).find_map(|offset| {
+            let (_, length, _) = check_base(&bytes[offset..]).ok()?;
+            let header = bytes.get(offset..offset.checked_add(length as usize)?)?;
+            // SAFETY: The header memory is fully covered by `bytes`.
+            let header = unsafe { Self::load(header.as_ptr().cast()) }.ok()?;
+            Some((offset, header))
+        })
+    }
+
+    /// Validates the header at the beginning of `bytes` like [`Self::load`],
+    /// but returns the byte offset of the first failing structure together
+    /// with the error or `None`, if the header is valid. For example, the
+    /// offset of a malformed tag is the offset of the tag in `bytes`. This
+    /// helps to pinpoint corruption, e.g., when triaging fuzzing results.
+    ///
+    /// In contrast to [`Self::load`], `bytes` doesn't need to be aligned.
+    #[must_use]
+    pub fn first_error(bytes: &[u8]) -> Option<(usize, LoadError)> {
+        let length = match check_base(bytes) {
+            Ok((_, length, _)) => length,
+            Err(err @ (LoadError::ZeroLength | LoadError::BadBaseLength)) => {
+                return Some((8, err));
+            }
+            Err(err @ LoadError::ChecksumMismatch) => return Some((12, err)),
+            Err(err) => return Some((0, err)),
+        };
+        check_tags(bytes, length).err()
+    }
+
+    /// Reads a Multiboot2 header from the current position of `reader`. First,
+    /// the fixed-size part of the header is read and verified. Then, exactly
+    /// the remaining bytes of the header are read. Hence, the whole file
+    /// doesn't need to be read upfront. The header is validated like
+    /// [`OwnedMultiboot2Header::from_vec`].
+    ///
+    /// Fails with [`std::io::ErrorKind::InvalidData`] if the header is
+    /// invalid. As the header must be within the first 32768 bytes of the
+    /// image, longer headers are rejected before their memory is allocated.
+    #[cfg(feature = "std")]
+    pub fn from_reader(reader: &mut impl std::io::Read) -> std::io::Result<OwnedMultiboot2Header> {
+        use std::io::{Error, ErrorKind};
+        use std::string::ToString;
+
+        let invalid_data = |err: LoadError| Error::new(ErrorKind::InvalidData, err.to_string());
+        let mut bytes = alloc::vec![0; size_of::<Multiboot2BasicHeader>()];
+        reader.read_exact(&mut bytes)?;
+        let (_, length, _) = check_base(&bytes).map_err(invalid_data)?;
+        if length as usize % ALIGNMENT != 0 || !Self::is_in_first_32k(length as usize - 1) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "invalid Multiboot2 header length",
+            ));
+        }
+
+        bytes.resize(length as usize, 0);
+        reader.read_exact(&mut bytes[size_of::<Multiboot2BasicHeader>()..])?;
+        OwnedMultiboot2Header::from_vec(bytes).map_err(invalid_data)
+    }
+
+    /// Returns a [`TagIter`] over all tags up to and including the first
+    /// [`crate::EndHeaderTag`]. Anything after it is not part of the tags.
     #[must_use]
     pub fn iter(&self) -> TagIter {
-        TagIter::new(self.0.payload())
+        TagIter::new(&self.0.payload()[..self.tags_len()])
+    }
+
+    /// Returns the number of bytes of the payload that are spanned by the
+    /// tags up to and including the first [`crate::EndHeaderTag`], including
+    /// their padding.
+    fn tags_len(&self) -> usize {
+        let payload = self.0.payload();
+        let mut offset = 0;
+        while offset < payload.len() {
+            // unwrap: `load` verified that all tags up to the end tag are at
+            // least as big as their header and lie within the payload.
+            let typ = u16::from_ne_bytes(payload[offset..offset + 2].try_into().unwrap());
+            let size = u32::from_ne_bytes(payload[offset + 4..offset + 8].try_into().unwrap());
+            offset += increase_to_alignment(size as usize);
+            if typ == HeaderTagType::End as u16 {
+                break;
+            }
+        }
+        offset
+    }
+
+    /// Returns an iterator over all tags casted to their concrete type. Tags of
+    /// an unknown type are emitted as [`ParsedHeaderTag::Unknown`]. Like
+    /// [`Self::iter`], the iterator stops after the first
+    /// [`crate::EndHeaderTag`].
+    pub fn tags(&self) -> impl Iterator<Item = ParsedHeaderTag<'_>> + '_ {
+        self.iter().map(ParsedHeaderTag::from_generic)
+    }
+
+    /// Returns the type, the flags, and the size of each tag up to the
+    /// [`crate::EndHeaderTag`]. In contrast to [`Self::tags`], only the tag
+    /// headers are read, which is sufficient for listing the tags. Tags of an
+    /// unknown type are skipped.
+    pub fn tag_summaries(&self) -> impl Iterator<Item = (HeaderTagType, HeaderTagFlag, u32)> + '_ {
+        self.iter()
+            .map(|tag| *tag.header())
+            .filter_map(|header| Some((header.typ().ok()?, header.flags(), header.size())))
+    }
+
+    /// Returns the deviations from the spec that [`Self::load`] tolerates.
+    /// [`Self::load_checked`] rejects these headers.
+    pub fn load_warnings(&self) -> impl Iterator<Item = LoadWarning> + '_ {
+        let oversized_end_tag = self.tags().filter_map(|tag| match tag {
+            ParsedHeaderTag::End(tag) if tag.size() as usize > size_of::<crate::EndHeaderTag>() => {
+                Some(LoadWarning::OversizedEndTag { size: tag.size() })
+            }
+            _ => None,
+        });
+        let duplicate_requests = self
+            .info_requests()
+            .enumerate()
+            .filter(|&(index, request)| self.info_requests().take(index).any(|r| r == request))
+            .map(|(_, request)| LoadWarning::DuplicateInfoRequest(request));
+        oversized_end_tag.chain(duplicate_requests)
+    }
+
+    /// Returns the requests of all [`InformationRequestHeaderTag`]s in order.
+    fn info_requests(&self) -> impl Iterator<Item = MbiTagType> + '_ {
+        self.tags()
+            .filter_map(|tag| match tag {
+                ParsedHeaderTag::InformationRequest(tag) => Some(tag.requested_types()),
+                _ => None,
+            })
+            .flatten()
+    }
+
+    /// Returns all tags up to the [`crate::EndHeaderTag`] as
+    /// [`crate::HeaderTag`] trait objects for dynamic dispatch. See
+    /// [`Self::tags`] for an enum-based alternative.
+    ///
+    /// Dynamically sized tags, i.e., the [`InformationRequestHeaderTag`] and
+    /// tags of an unknown type, are skipped, as they can't be trait objects.
+    /// See [`ParsedHeaderTag::as_dyn`].
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn tag_objects(&self) -> Vec<&dyn crate::HeaderTag> {
+        self.tags().filter_map(|tag| tag.as_dyn()).collect()
+    }
+
+    /// Returns the difference between the reported length of the header and
+    /// the bytes that are actually spanned by the base header and all tags up
+    /// to the [`crate::EndHeaderTag`]. A nonzero value indicates corruption
+    /// or trailing bytes.
+    #[must_use]
+    pub fn length_discrepancy(&self) -> i64 {
+        let span = self
+            .tags()
+            .zip(self.tag_byte_ranges())
+            .map(|(_, range)| range.end)
+            .last()
+            .unwrap_or(size_of::<Multiboot2BasicHeader>());
+        i64::from(self.length()) - span as i64
+    }
+
+    /// Returns the number of padding bytes between the tags up to the
+    /// [`crate::EndHeaderTag`], i.e., the bytes that align each tag to an
+    /// 8-byte boundary. Bytes after the end tag are not counted, see
+    /// [`Self::length_discrepancy`] for them.
+    #[must_use]
+    pub fn padding_bytes(&self) -> usize {
+        self.tags()
+            .zip(self.tag_byte_ranges())
+            .map(|(_, range)| increase_to_alignment(range.len()) - range.len())
+            .sum()
+    }
+
+    /// Returns the byte range `[start, end)` of each tag relative to the
+    /// beginning of the header. The range doesn't include the padding that
+    /// follows a tag. Loaders can use this to protect the header memory.
+    pub fn tag_byte_ranges(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        let base = ptr::addr_of!(*self.0).cast::<u8>() as usize;
+        self.iter().map(move |tag| {
+            let start = ptr::addr_of!(*tag).cast::<u8>() as usize - base;
+            start..start + tag.header().size() as usize
+        })
+    }
+
+    /// Returns whether any tag has reserved bits of its flags set. See
+    /// [`HeaderTagHeader::has_reserved_flag_bits`]. Strict loaders may
+    /// reject such headers.
+    #[must_use]
+    pub fn has_reserved_flag_bits(&self) -> bool {
+        self.iter().any(|tag| tag.header().has_reserved_flag_bits())
+    }
+
+    /// Returns an iterator over all tags of the header whose [`HeaderTagType`]
+    /// is contained in `supported`. This way, a loader only processes tags
+    /// that it understands. Use [`Self::unsupported_required_tags`] to find
+    /// out whether the loader must refuse to load the payload.
+    pub fn supported_tags<'b>(
+        &'b self,
+        supported: &'b [HeaderTagType],
+    ) -> impl Iterator<Item = ParsedHeaderTag<'b>> + 'b {
+        self.iter()
+            .map(ParsedHeaderTag::from_generic)
+            .filter(move |tag| tag.typ().is_ok_and(|typ| supported.contains(&typ)))
+    }
+
+    /// Returns an iterator over all tags of the header that are marked as
+    /// [`HeaderTagFlag::Required`] but whose [`HeaderTagType`] is not
+    /// contained in `supported`. Required tags of an unknown type are always
+    /// reported. The [`EndHeaderTag`] is never reported.
+    ///
+    /// If this yields any tag, the loader must fail loading the payload.
+    ///
+    /// [`EndHeaderTag`]: crate::EndHeaderTag
+    pub fn unsupported_required_tags<'b>(
+        &'b self,
+        supported: &'b [HeaderTagType],
+    ) -> impl Iterator<Item = ParsedHeaderTag<'b>> + 'b {
+        self.iter()
+            .map(ParsedHeaderTag::from_generic)
+            .filter(|tag| tag.typ() != Ok(HeaderTagType::End))
+            .filter(|tag| tag.flags() == HeaderTagFlag::Required)
+            .filter(move |tag| !tag.typ().is_ok_and(|typ| supported.contains(&typ)))
+    }
+
+    /// Calls the corresponding method of the [`HeaderTagVisitor`] for each
+    /// tag of the header, in order.
+    pub fn visit<V: HeaderTagVisitor>(&self, visitor: &mut V) {
+        for tag in self.iter().map(ParsedHeaderTag::from_generic) {
+            match tag {
+                ParsedHeaderTag::End(tag) => visitor.visit_end(tag),
+                ParsedHeaderTag::InformationRequest(tag) => visitor.visit_information_request(tag),
+                ParsedHeaderTag::Address(tag) => visitor.visit_address(tag),
+                ParsedHeaderTag::EntryAddress(tag) => visitor.visit_entry_address(tag),
+                ParsedHeaderTag::ConsoleFlags(tag) => visitor.visit_console(tag),
+                ParsedHeaderTag::Framebuffer(tag) => visitor.visit_framebuffer(tag),
+                ParsedHeaderTag::ModuleAlign(tag) => visitor.visit_module_align(tag),
+                ParsedHeaderTag::EfiBS(tag) => visitor.visit_efi_bs(tag),
+                ParsedHeaderTag::EntryAddressEFI32(tag) => visitor.visit_entry_efi32(tag),
+                ParsedHeaderTag::EntryAddressEFI64(tag) => visitor.visit_entry_efi64(tag),
+                ParsedHeaderTag::Relocatable(tag) => visitor.visit_relocatable(tag),
+                ParsedHeaderTag::Unknown(tag) => visitor.visit_unknown(tag),
+            }
+        }
+    }
+
+    /// Returns the raw types of all tags that are unknown to this crate, in
+    /// order. Tooling can use this to report nonstandard tags.
+    pub fn unknown_tag_types(&self) -> impl Iterator<Item = u16> + '_ {
+        self.iter()
+            .map(|tag| tag.header())
+            .filter(|header| header.typ().is_err())
+            .map(HeaderTagHeader::typ_raw)
+    }
+
+    /// Collects all tags of the header into an array with a capacity of `N`
+    /// tags, which avoids heap allocations. Returns the array together with
+    /// the number of tags. Slots behind the last tag are `None`.
+    ///
+    /// Fails if the header contains more than `N` tags.
+    pub fn collect_tags<const N: usize>(
+        &self,
+    ) -> Result<([Option<ParsedHeaderTag<'_>>; N], usize), CollectError> {
+        let mut tags = [None; N];
+        let mut count = 0;
+        for tag in self.iter().map(ParsedHeaderTag::from_generic) {
+            let slot = tags.get_mut(count).ok_or(CollectError { capacity: N })?;
+            *slot = Some(tag);
+            count += 1;
+        }
+        Ok((tags, count))
+    }
+
+    /// Splits all tags of the header into the tags marked as
+    /// [`HeaderTagFlag::Required`] and the tags marked as
+    /// [`HeaderTagFlag::Optional`], in that order. A loader must fail if it
+    /// can't satisfy any of the required tags.
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn partition_tags(&self) -> (Vec<ParsedHeaderTag<'_>>, Vec<ParsedHeaderTag<'_>>) {
+        self.iter()
+            .map(ParsedHeaderTag::from_generic)
+            .partition(|tag| tag.flags() == HeaderTagFlag::Required)
+    }
+
+    /// Returns the requested boot information tag types in the order of the
+    /// requests across all [`InformationRequestHeaderTag`]s. Types requested
+    /// multiple times are only listed once. A loader may use this to order
+    /// the tags of the boot information deterministically.
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn mbi_tag_order(&self) -> Vec<MbiTagType> {
+        let mut order = Vec::new();
+        for request in self.info_requests() {
+            if !order.contains(&request) {
+                order.push(request);
+            }
+        }
+        order
+    }
+
+    /// Emits Rust source code that reconstructs this header using the
+    /// `Builder` (requires the `builder` feature). This helps to learn the
+    /// API from an existing binary header.
+    ///
+    /// The [`crate::EndHeaderTag`] is not part of the output, as the builder
+    /// doesn't need it. Tags of an unknown type are emitted as comments.
+    ///
+    /// Fails if the header contains values that the builder can't express,
+    /// such as an unknown architecture.
+    #[cfg(feature = "alloc")]
+    pub fn to_builder_source(&self) -> Result<String, BuilderSourceError> {
+        let arch = self.arch().map_err(BuilderSourceError::UnknownArch)?;
+        let mut src = String::new();
+        // unwrap: Writing to a String never fails.
+        writeln!(src, "Builder::new(HeaderTagISA::{arch:?})").unwrap();
+        for tag in self.iter().map(ParsedHeaderTag::from_generic) {
+            let flags = tag.flags();
+            match tag {
+                ParsedHeaderTag::End(_) => continue,
+                ParsedHeaderTag::InformationRequest(tag) => {
+                    writeln!(
+                        src,
+                        "    .information_request_tag(InformationRequestHeaderTag::new(\n        \
+                         HeaderTagFlag::{flags:?},\n        &["
+                    )
+                    .unwrap();
+                    for &request in tag.requests() {
+                        let request = MbiTagType::from(request);
+                        writeln!(src, "            MbiTagType::{request:?}.into(),").unwrap();
+                    }
+                    writeln!(src, "        ],\n    ))").unwrap();
+                }
+                ParsedHeaderTag::Address(tag) => writeln!(
+                    src,
+                    "    .address_tag(AddressHeaderTag::new(HeaderTagFlag::{flags:?}, {:#x}, {:#x}, {:#x}, {:#x}))",
+                    tag.header_addr(),
+                    tag.load_addr(),
+                    tag.load_end_addr(),
+                    tag.bss_end_addr()
+                )
+                .unwrap(),
+                ParsedHeaderTag::EntryAddress(tag) => writeln!(
+                    src,
+                    "    .entry_tag(EntryAddressHeaderTag::new(HeaderTagFlag::{flags:?}, {:#x}))",
+                    tag.entry_addr()
+                )
+                .unwrap(),
+                ParsedHeaderTag::ConsoleFlags(tag) => writeln!(
+                    src,
+                    "    .console_tag(ConsoleHeaderTag::new(HeaderTagFlag::{flags:?}, ConsoleHeaderTagFlags::{:?}))",
+                    tag.console_flags()
+                )
+                .unwrap(),
+                ParsedHeaderTag::Framebuffer(tag) => writeln!(
+                    src,
+                    "    .framebuffer_tag(FramebufferHeaderTag::new(HeaderTagFlag::{flags:?}, {}, {}, {}))",
+                    tag.width(),
+                    tag.height(),
+                    tag.depth()
+                )
+                .unwrap(),
+                ParsedHeaderTag::ModuleAlign(_) => writeln!(
+                    src,
+                    "    .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::{flags:?}))"
+                )
+                .unwrap(),
+                ParsedHeaderTag::EfiBS(_) => writeln!(
+                    src,
+                    "    .efi_bs_tag(EfiBootServiceHeaderTag::new(HeaderTagFlag::{flags:?}))"
+                )
+                .unwrap(),
+                ParsedHeaderTag::EntryAddressEFI32(tag) => writeln!(
+                    src,
+                    "    .efi_32_tag(EntryEfi32HeaderTag::new(HeaderTagFlag::{flags:?}, {:#x}))",
+                    tag.entry_addr()
+                )
+                .unwrap(),
+                ParsedHeaderTag::EntryAddressEFI64(tag) => writeln!(
+                    src,
+                    "    .efi_64_tag(EntryEfi64HeaderTag::new(HeaderTagFlag::{flags:?}, {:#x}))",
+                    tag.entry_addr()
+                )
+                .unwrap(),
+                ParsedHeaderTag::Relocatable(tag) => writeln!(
+                    src,
+                    "    .relocatable_tag(RelocatableHeaderTag::new(HeaderTagFlag::{flags:?}, {:#x}, {:#x}, {:#x}, RelocatableHeaderTagPreference::{:?}))",
+                    tag.min_addr(),
+                    tag.max_addr(),
+                    tag.align(),
+                    tag.preference()
+                        .map_err(BuilderSourceError::UnknownRelocatableHeaderTagPreference)?
+                )
+                .unwrap(),
+                ParsedHeaderTag::Unknown(tag) => writeln!(
+                    src,
+                    "    // Unsupported tag of unknown type {}",
+                    tag.header().typ_raw()
+                )
+                .unwrap(),
+            }
+        }
+        src.push_str("    .build()");
+        Ok(src)
+    }
+
+    /// Emits a snippet for a GNU ld linker script that places the header in
+    /// the output section `section_name` with the alignment required by the
+    /// spec. The section must be placed within the first 32768 bytes of the
+    /// image, which the snippet notes in a comment.
+    ///
+    /// The header must be emitted into an input section of the same name,
+    /// e.g., with `#[link_section]`.
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn linker_snippet(&self, section_name: &str) -> String {
+        let mut src = String::new();
+        // unwrap: Writing to a String never fails.
+        writeln!(
+            src,
+            "/* Multiboot2 header ({} bytes): {ALIGNMENT}-byte aligned, within the first 32768 bytes of the image. */",
+            self.length()
+        )
+        .unwrap();
+        writeln!(src, "{section_name} ALIGN({ALIGNMENT}) :").unwrap();
+        writeln!(src, "{{").unwrap();
+        writeln!(src, "    KEEP(*({section_name}))").unwrap();
+        src.push('}');
+        src
+    }
+
+    /// Returns the bytes of a compact copy of this header. All tags up to the
+    /// first [`crate::EndHeaderTag`] are kept, so that required tags stay
+    /// processable, but they are reordered to minimize the padding between
+    /// them: Tags that need less padding come first. Tags that need the same
+    /// padding keep their relative order. Bytes after the end tag are dropped
+    /// and the copy is terminated by a new [`crate::EndHeaderTag`]. Length and
+    /// checksum are updated accordingly.
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn repack(&self) -> Vec<u8> {
+        let mut tags = self
+            .iter()
+            .take_while(|tag| tag.header().typ() != Ok(HeaderTagType::End))
+            .collect::<Vec<_>>();
+        // A stable sort, as the relative order of tags might be relevant.
+        tags.sort_by_key(|tag| tag.as_bytes().len() - tag.header().size() as usize);
+        let mut payload = Vec::new();
+        tags.iter()
+            .for_each(|tag| payload.extend(tag.as_bytes().iter()));
+        payload.extend_from_slice(&crate::EndHeaderTag::BYTES);
+        let owned = OwnedMultiboot2Header::new(self.0.header().arch, &payload);
+        owned.header().as_bytes().to_vec()
+    }
+
+    /// Takes all decisions that a loader needs to take to boot the payload in
+    /// the given [`BootContext`].
+    ///
+    /// The EFI entry address tags are only considered if the machine was
+    /// booted via UEFI and the header contains an [`EfiBootServiceHeaderTag`].
+    /// Otherwise, the [`EntryAddressHeaderTag`] is used, if present, and the
+    /// ELF entry point as fallback.
+    #[must_use]
+    pub fn loader_plan(&self, ctx: BootContext) -> LoaderPlan {
+        let keep_boot_services = ctx.efi && self.efi_boot_services_tag().is_some();
+        let efi_entry = if !keep_boot_services {
+            None
+        } else if ctx.firmware_64bit {
+            self.entry_address_efi64_tag()
+                .map(|tag| EntryPoint::Efi64(tag.entry_addr()))
+        } else {
+            self.entry_address_efi32_tag()
+                .map(|tag| EntryPoint::Efi32(tag.entry_addr()))
+        };
+        let entry = efi_entry
+            .or_else(|| {
+                self.entry_address_tag()
+                    .map(|tag| EntryPoint::Address(tag.entry_addr()))
+            })
+            .unwrap_or(EntryPoint::Elf);
+
+        let display = match (self.framebuffer_tag(), self.console_flags_tag()) {
+            (Some(tag), _) => DisplayPreference::Framebuffer {
+                width: tag.width(),
+                height: tag.height(),
+                depth: tag.depth(),
+            },
+            (None, Some(tag)) if tag.console_flags() == ConsoleHeaderTagFlags::EgaTextSupported => {
+                DisplayPreference::EgaText
+            }
+            _ => DisplayPreference::None,
+        };
+
+        let load_base = self
+            .relocatable_tag()
+            .and_then(|tag| tag.choose_base(ctx.image_size, ctx.free_memory.iter().copied()));
+
+        LoaderPlan {
+            load_base,
+            entry,
+            display,
+            page_align_modules: self.module_align_tag().is_some(),
+            keep_boot_services,
+        }
+    }
+
+    /// Returns whether both headers lead to the same [`LoaderPlan`] in the
+    /// given [`BootContext`], i.e., whether a loader boots both payloads in
+    /// the same way. This is more meaningful than comparing the raw bytes,
+    /// as tags that don't affect the boot process are ignored.
+    #[must_use]
+    pub fn boot_equivalent(&self, other: &Self, ctx: BootContext) -> bool {
+        self.loader_plan(ctx) == other.loader_plan(ctx)
+    }
+
+    /// Checks that the load range of the [`AddressHeaderTag`] lies within the
+    /// `[min_addr, max_addr]` range of the [`RelocatableHeaderTag`].
+    ///
+    /// The load range spans from `load_addr` to the bigger value of
+    /// `load_end_addr` and `bss_end_addr`. If not both tags are present,
+    /// there is nothing to compare and this returns `true`.
+    #[must_use]
+    pub fn address_ranges_consistent(&self) -> bool {
+        let (Some(address), Some(relocatable)) = (self.address_tag(), self.relocatable_tag())
+        else {
+            return true;
+        };
+        let start = address.load_addr();
+        let end = address
+            .load_end_addr()
+            .max(address.bss_end_addr())
+            .max(start);
+        start >= relocatable.min_addr() && end <= relocatable.max_addr()
+    }
+
+    /// Checks whether all physical addresses in the header are below
+    /// `ceiling`, e.g., for loaders that are restricted to low memory. This
+    /// covers the fields of the [`AddressHeaderTag`], the `max_addr` of the
+    /// [`RelocatableHeaderTag`], and the entry addresses. The
+    /// [`FramebufferHeaderTag`] carries no address and is therefore not
+    /// relevant.
+    #[must_use]
+    pub fn all_addresses_below(&self, ceiling: u64) -> bool {
+        self.tags().all(|tag| {
+            let below = |addr: u32| u64::from(addr) < ceiling;
+            match tag {
+                ParsedHeaderTag::Address(tag) => {
+                    below(tag.header_addr())
+                        && below(tag.load_addr())
+                        && below(tag.load_end_addr())
+                        && below(tag.bss_end_addr())
+                }
+                ParsedHeaderTag::EntryAddress(tag) => below(tag.entry_addr()),
+                ParsedHeaderTag::EntryAddressEFI32(tag) => below(tag.entry_addr()),
+                ParsedHeaderTag::EntryAddressEFI64(tag) => below(tag.entry_addr()),
+                ParsedHeaderTag::Relocatable(tag) => below(tag.max_addr()),
+                _ => true,
+            }
+        })
+    }
+
+    /// Computes the CRC32 (IEEE 802.3) of the tag region, i.e., of all bytes
+    /// following the [`Multiboot2BasicHeader`]. In contrast to the checksum
+    /// of the spec, this covers the content of all tags and helps tooling to
+    /// detect modified headers.
+    #[must_use]
+    pub fn tag_region_crc32(&self) -> u32 {
+        let mut crc = !0_u32;
+        for &byte in self.0.payload() {
+            crc ^= u32::from(byte);
+            for _ in 0..8 {
+                let mask = (crc & 1).wrapping_neg();
+                crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+            }
+        }
+        !crc
     }
 
     /// Wrapper around [`Multiboot2BasicHeader::verify_checksum`].
@@ -118,20 +773,76 @@ impl<'a> Multiboot2Header<'a> {
         self.0.header().header_magic()
     }
     /// Wrapper around [`Multiboot2BasicHeader::arch`].
-    #[must_use]
-    pub const fn arch(&self) -> HeaderTagISA {
+    pub const fn arch(&self) -> Result<HeaderTagISA, UnknownHeaderTagISA> {
         self.0.header().arch()
     }
+    /// Wrapper around [`Multiboot2BasicHeader::arch_raw`].
+    #[must_use]
+    pub const fn arch_raw(&self) -> u32 {
+        self.0.header().arch_raw()
+    }
+    /// Returns the declared architecture, or the raw value if it is no known
+    /// [`HeaderTagISA`]. Like [`Self::arch`], but for loaders that branch on
+    /// the raw value of an unknown architecture.
+    pub const fn architecture(&self) -> Result<HeaderTagISA, u32> {
+        match self.arch() {
+            Ok(arch) => Ok(arch),
+            Err(UnknownHeaderTagISA(raw)) => Err(raw),
+        }
+    }
     /// Wrapper around [`Multiboot2BasicHeader::length`].
     #[must_use]
     pub const fn length(&self) -> u32 {
         self.0.header().length()
     }
+    /// Returns the bytes of the whole header, starting at the magic value and
+    /// with exactly [`Self::length`] bytes. This is the canonical way to copy,
+    /// hash, or re-emit a parsed header.
+    #[must_use]
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        let ptr = ptr::addr_of!(*self.0).cast::<u8>();
+        // SAFETY: `load` verified that the memory covers the reported length.
+        unsafe { core::slice::from_raw_parts(ptr, self.length() as usize) }
+    }
+    /// Returns whether the whole header, as reported by its length, is not
+    /// bigger than `max_bytes`. Loaders may limit the header size they read.
+    #[must_use]
+    pub const fn fits_within(&self, max_bytes: usize) -> bool {
+        self.length() as usize <= max_bytes
+    }
+    /// Returns whether a header at `offset` in the image starts within the
+    /// first 32768 bytes, as the spec demands. See also
+    /// [`Self::fits_in_first_32k`].
+    #[must_use]
+    pub const fn is_in_first_32k(offset: usize) -> bool {
+        offset < 32768
+    }
+    /// Returns whether this header, located at `offset` in the image, is
+    /// completely contained in the first 32768 bytes. Loaders reject headers
+    /// beyond this limit.
+    #[must_use]
+    pub const fn fits_in_first_32k(&self, offset: usize) -> bool {
+        match offset.checked_add(self.length() as usize) {
+            Some(end) => end <= 32768,
+            None => false,
+        }
+    }
     /// Wrapper around [`Multiboot2BasicHeader::checksum`].
     #[must_use]
     pub const fn checksum(&self) -> u32 {
         self.0.header().checksum()
     }
+    /// Returns all fields of the [`Multiboot2BasicHeader`] at once.
+    #[must_use]
+    pub const fn base(&self) -> BaseHeader {
+        let header = self.0.header();
+        BaseHeader {
+            magic: header.header_magic(),
+            arch: header.arch_raw(),
+            header_length: header.length(),
+            checksum: header.checksum(),
+        }
+    }
     /// Wrapper around [`Multiboot2BasicHeader::calc_checksum`].
     #[must_use]
     pub const fn calc_checksum(magic: u32, arch: HeaderTagISA, length: u32) -> u32 {
@@ -180,6 +891,104 @@ impl<'a> Multiboot2Header<'a> {
         self.get_tag()
     }
 
+    /// Returns whether the payload relies on the entry point of its ELF
+    /// header. This is the case if the header contains neither an
+    /// [`AddressHeaderTag`] nor any of the entry address tags
+    /// ([`EntryAddressHeaderTag`], [`EntryEfi32HeaderTag`],
+    /// [`EntryEfi64HeaderTag`]).
+    #[must_use]
+    pub fn needs_elf_entry(&self) -> bool {
+        !self.tags().any(|tag| {
+            matches!(
+                tag,
+                ParsedHeaderTag::Address(_)
+                    | ParsedHeaderTag::EntryAddress(_)
+                    | ParsedHeaderTag::EntryAddressEFI32(_)
+                    | ParsedHeaderTag::EntryAddressEFI64(_)
+            )
+        })
+    }
+
+    /// Returns whether a loader that only supports 32-bit x86 can boot the
+    /// payload. This is the case if the architecture is [`HeaderTagISA::I386`],
+    /// the header has no [`EntryEfi64HeaderTag`], and the
+    /// [`RelocatableHeaderTag`], if present, is satisfiable. As all addresses
+    /// of the tags are 32-bit values, any placement is below 4 GiB.
+    #[must_use]
+    pub fn is_32bit_bootable(&self) -> bool {
+        self.arch() == Ok(HeaderTagISA::I386)
+            && self.entry_address_efi64_tag().is_none()
+            && self
+                .relocatable_tag()
+                .map_or(true, RelocatableHeaderTag::is_satisfiable)
+    }
+
+    /// Summarizes all EFI-related tags of the header.
+    #[must_use]
+    pub fn efi_tags(&self) -> EfiTagSummary {
+        let efi_info_requests = self.info_requests().any(|request| {
+            matches!(
+                request,
+                MbiTagType::Efi32
+                    | MbiTagType::Efi64
+                    | MbiTagType::EfiMmap
+                    | MbiTagType::EfiBs
+                    | MbiTagType::Efi32Ih
+                    | MbiTagType::Efi64Ih
+            )
+        });
+        EfiTagSummary {
+            boot_services: self.efi_boot_services_tag().is_some(),
+            efi32_entry: self.entry_address_efi32_tag().is_some(),
+            efi64_entry: self.entry_address_efi64_tag().is_some(),
+            efi_info_requests,
+        }
+    }
+
+    /// Returns whether the payload requests the ACPI RSDP tags of the boot
+    /// information as `(v1, v2)`, i.e., whether [`MbiTagType::AcpiV1`] or
+    /// [`MbiTagType::AcpiV2`] are part of any [`InformationRequestHeaderTag`].
+    #[must_use]
+    pub fn requests_acpi(&self) -> (bool, bool) {
+        (
+            self.requests_info(MbiTagType::AcpiV1),
+            self.requests_info(MbiTagType::AcpiV2),
+        )
+    }
+
+    /// Returns whether the payload requests the physical load base address of
+    /// the image, i.e., whether [`MbiTagType::LoadBaseAddr`] is part of any
+    /// [`InformationRequestHeaderTag`]. This is common for relocatable images.
+    #[must_use]
+    pub fn requests_load_base(&self) -> bool {
+        self.requests_info(MbiTagType::LoadBaseAddr)
+    }
+
+    /// Returns whether the header contains a [`FramebufferHeaderTag`], i.e.,
+    /// whether the payload requests a graphical framebuffer.
+    #[must_use]
+    pub fn requests_framebuffer(&self) -> bool {
+        self.framebuffer_tag().is_some()
+    }
+
+    /// Checks that the header doesn't require EGA text mode and a graphical
+    /// framebuffer at the same time, as a loader can only satisfy one of
+    /// them. This is the case if both a [`ConsoleHeaderTag`] with
+    /// [`ConsoleHeaderTagFlags::EgaTextSupported`] and a
+    /// [`FramebufferHeaderTag`] are present and marked as
+    /// [`HeaderTagFlag::Required`].
+    #[must_use]
+    pub fn display_tags_consistent(&self) -> bool {
+        let requires_ega_text = self.console_flags_tag().is_some_and(|tag| {
+            tag.flags() == HeaderTagFlag::Required
+                && tag.console_flags() == ConsoleHeaderTagFlags::EgaTextSupported
+        });
+        let requires_framebuffer = self
+            .framebuffer_tag()
+            .is_some_and(|tag| tag.flags() == HeaderTagFlag::Required);
+        !(requires_ega_text && requires_framebuffer)
+    }
+
     /// Search for the [`ModuleAlignHeaderTag`] header tag.
     #[must_use]
     pub fn module_align_tag(&self) -> Option<&ModuleAlignHeaderTag> {
@@ -198,14 +1007,31 @@ impl<'a> Multiboot2Header<'a> {
         self.get_tag()
     }
 
+    /// Returns whether any [`InformationRequestHeaderTag`] contains `typ`.
+    fn requests_info(&self, typ: MbiTagType) -> bool {
+        self.info_requests().any(|request| request == typ)
+    }
+
     /// Searches for the specified tag by iterating the structure and returns
-    /// the first occurrence, if present.
+    /// the first occurrence, if present. The tag is identified by its
+    /// [`Tag::ID`], e.g., `header.get_tag::<RelocatableHeaderTag>()`.
+    ///
+    /// If the header contains the tag multiple times, only the first one is
+    /// returned. Use [`Self::get_tags`] to get all of them.
     #[must_use]
-    fn get_tag<T: Tag<IDType = HeaderTagType, Header = HeaderTagHeader> + ?Sized + 'a>(
+    pub fn get_tag<T: Tag<IDType = HeaderTagType, Header = HeaderTagHeader> + ?Sized + 'a>(
         &'a self,
     ) -> Option<&'a T> {
+        self.get_tags().next()
+    }
+
+    /// Returns an iterator over all occurrences of the specified tag in the
+    /// order of the structure. See [`Self::get_tag`].
+    pub fn get_tags<T: Tag<IDType = HeaderTagType, Header = HeaderTagHeader> + ?Sized + 'a>(
+        &'a self,
+    ) -> impl Iterator<Item = &'a T> + 'a {
         self.iter()
-            .find(|tag| tag.header().typ() == T::ID)
+            .filter(|tag| tag.header().typ() == Ok(T::ID))
             .map(|tag| tag.cast::<T>())
     }
 }
@@ -223,17 +1049,292 @@ impl Debug for Multiboot2Header<'_> {
     }
 }
 
+/// A Multiboot2 header on the heap, which is properly aligned.
+///
+/// See [`Self::from_vec`] and [`Multiboot2Header::find_in_realigned`].
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct OwnedMultiboot2Header(OwnedHeaderBytes);
+
+/// The storage of an [`OwnedMultiboot2Header`].
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+enum OwnedHeaderBytes {
+    /// A vector whose allocation happens to be 8-byte aligned. It contains
+    /// exactly the bytes of the header.
+    Vec(Vec<u8>),
+    /// A properly aligned copy.
+    Boxed(Box<DynSizedStructure<Multiboot2BasicHeader>>),
+}
+
+#[cfg(feature = "alloc")]
+impl OwnedMultiboot2Header {
+    /// Creates a properly aligned copy of a header with the given raw
+    /// architecture and the bytes of all tags.
+    fn new(arch: u32, payload: &[u8]) -> Self {
+        // Length and checksum are updated by `new_boxed`.
+        let header = Multiboot2BasicHeader {
+            header_magic: MAGIC,
+            arch,
+            length: 0,
+            checksum: 0,
+        };
+        Self(OwnedHeaderBytes::Boxed(new_boxed(header, &[payload])))
+    }
+
+    /// Parses a Multiboot2 header from the beginning of `bytes`, which is
+    /// the most convenient way in hosted environments. If the allocation of
+    /// `bytes` is 8-byte aligned, it is reused and trailing bytes after the
+    /// header are dropped. Otherwise, the header is copied into a properly
+    /// aligned buffer.
+    ///
+    /// This performs the same checks as [`Multiboot2Header::load`].
+    pub fn from_vec(mut bytes: Vec<u8>) -> Result<Self, LoadError> {
+        if bytes.as_ptr().align_offset(ALIGNMENT) != 0 {
+            return Self::copy_from(&bytes);
+        }
+        let (_, length, _) = check_base(&bytes)?;
+        check_tags(&bytes, length).map_err(|(_, err)| err)?;
+        bytes.truncate(length as usize);
+        Ok(Self(OwnedHeaderBytes::Vec(bytes)))
+    }
+
+    /// Validates the header at the beginning of `bytes` like [`Self::from_vec`]
+    /// and copies it into a properly aligned buffer. Nothing is copied if the
+    /// header is invalid.
+    fn copy_from(bytes: &[u8]) -> Result<Self, LoadError> {
+        let (arch, length, _) = check_base(bytes)?;
+        check_tags(bytes, length).map_err(|(_, err)| err)?;
+        let payload = &bytes[size_of::<Multiboot2BasicHeader>()..length as usize];
+        Ok(Self::new(arch, payload))
+    }
+
+    /// Returns the [`Multiboot2Header`] view on the owned bytes.
+    ///
+    /// This type doesn't implement `Deref<Target = Multiboot2Header>`, as a
+    /// [`Multiboot2Header`] is a borrowed view itself and can't be stored
+    /// next to the bytes it borrows.
+    #[must_use]
+    pub fn header(&self) -> Multiboot2Header<'_> {
+        match &self.0 {
+            OwnedHeaderBytes::Vec(bytes) => {
+                // unwrap: The header was validated on construction.
+                Multiboot2Header(DynSizedStructure::ref_from_slice(bytes).unwrap())
+            }
+            OwnedHeaderBytes::Boxed(structure) => Multiboot2Header(structure),
+        }
+    }
+}
+
+/// Intermediate steps of the checksum calculation. All additions and
+/// subtractions are wrapping. See
+/// [`Multiboot2BasicHeader::calc_checksum_verbose`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChecksumSteps {
+    /// `magic + arch`
+    pub magic_plus_arch: u32,
+    /// `magic + arch + length`
+    pub sum: u32,
+    /// `0 - (magic + arch + length)`, i.e., the checksum.
+    pub checksum: u32,
+}
+
+/// The raw fields of the [`Multiboot2BasicHeader`] of a [`Multiboot2Header`].
+/// See [`Multiboot2Header::base`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BaseHeader {
+    /// The magic value, which is [`MAGIC`] for valid headers.
+    pub magic: u32,
+    /// The raw architecture. See [`HeaderTagISA`].
+    pub arch: u32,
+    /// The length of the whole header, including all tags.
+    pub header_length: u32,
+    /// The checksum of the header.
+    pub checksum: u32,
+}
+
+/// Summary of all EFI-related tags of a [`Multiboot2Header`]. See
+/// [`Multiboot2Header::efi_tags`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EfiTagSummary {
+    /// Whether the header contains an [`EfiBootServiceHeaderTag`].
+    pub boot_services: bool,
+    /// Whether the header contains an [`EntryEfi32HeaderTag`].
+    pub efi32_entry: bool,
+    /// Whether the header contains an [`EntryEfi64HeaderTag`].
+    pub efi64_entry: bool,
+    /// Whether the [`InformationRequestHeaderTag`] requests any EFI-related
+    /// boot information tag.
+    pub efi_info_requests: bool,
+}
+
+/// Reads the fields of the [`Multiboot2BasicHeader`] at the beginning of
+/// `bytes` in native endianness, as written by the builder, and checks the
+/// magic value, the length, and the checksum. `bytes` doesn't need to be
+/// aligned or to cover the tags.
+///
+/// Returns the raw architecture, the length, and the checksum.
+fn check_base(bytes: &[u8]) -> Result<(u32, u32, u32), LoadError> {
+    let read_u32 = |index: usize| {
+        let from = index * size_of::<u32>();
+        u32::from_ne_bytes(bytes[from..from + size_of::<u32>()].try_into().unwrap())
+    };
+    if bytes.len() < size_of::<Multiboot2BasicHeader>() {
+        return Err(LoadError::Memory(MemoryError::ShorterThanHeader));
+    }
+    let (magic, arch, length, checksum) = (read_u32(0), read_u32(1), read_u32(2), read_u32(3));
+    if magic != MAGIC {
+        return Err(LoadError::MagicNotFound);
+    }
+    if length == 0 {
+        return Err(LoadError::ZeroLength);
+    }
+    if (length as usize) < size_of::<Multiboot2BasicHeader>() {
+        return Err(LoadError::BadBaseLength);
+    }
+    if checksum != Multiboot2BasicHeader::calc_checksum_raw(magic, arch, length) {
+        return Err(LoadError::ChecksumMismatch);
+    }
+    Ok((arch, length, checksum))
+}
+
+/// Walks the tags of the header at the beginning of `bytes`, whose
+/// [`Multiboot2BasicHeader`] reports `length` and already passed
+/// [`check_base`]. Tags of a type with a fixed size must have exactly this
+/// size. The walk stops at the first [`crate::EndHeaderTag`]. `bytes` doesn't
+/// need to be aligned.
+///
+/// This is the single validation of the tags for all ways to load a header,
+/// so that no tag is constructed before its size was checked. Returns the
+/// offset of the first failing structure in `bytes` together with the error.
+fn check_tags(bytes: &[u8], length: u32) -> Result<(), (usize, LoadError)> {
+    let length = length as usize;
+    if length > bytes.len() {
+        return Err((8, LoadError::Memory(MemoryError::InvalidReportedTotalSize)));
+    }
+    if length % ALIGNMENT != 0 {
+        return Err((8, LoadError::Memory(MemoryError::MissingPadding)));
+    }
+    // unwrap: All reads are within `length` bytes.
+    let read_u16 = |from: usize| u16::from_ne_bytes(bytes[from..from + 2].try_into().unwrap());
+    let read_u32 = |from: usize| u32::from_ne_bytes(bytes[from..from + 4].try_into().unwrap());
+
+    let mut entry_address_tags = 0;
+    let mut offset = size_of::<Multiboot2BasicHeader>();
+    while offset < length {
+        if offset + size_of::<HeaderTagHeader>() > length {
+            return Err((offset, LoadError::Memory(MemoryError::ShorterThanHeader)));
+        }
+        let typ = HeaderTagType::from_u16(read_u16(offset));
+        let size = read_u32(offset + 4);
+        let fixed_size = typ.ok().and_then(fixed_tag_size);
+        if (size as usize) < size_of::<HeaderTagHeader>()
+            || size as usize > length - offset
+            || fixed_size.is_some_and(|fixed_size| size as usize != fixed_size)
+        {
+            return Err((
+                offset,
+                LoadError::Memory(MemoryError::InvalidReportedTotalSize),
+            ));
+        }
+        match typ {
+            Ok(HeaderTagType::End) => break,
+            Ok(HeaderTagType::InformationRequest)
+                if (size as usize - size_of::<HeaderTagHeader>()) % size_of::<MbiTagTypeId>()
+                    != 0 =>
+            {
+                return Err((offset, LoadError::MalformedInfoRequest { size }));
+            }
+            Ok(HeaderTagType::EntryAddress) => {
+                entry_address_tags += 1;
+                if entry_address_tags > 1 {
+                    return Err((offset, LoadError::DuplicateEntryAddress));
+                }
+            }
+            _ => {}
+        }
+        offset += increase_to_alignment(size as usize);
+    }
+    Ok(())
+}
+
+/// Returns the size of the tags of type `typ` as defined by the spec, if it is
+/// fixed. The
+/// [`InformationRequestHeaderTag`] is dynamically sized and oversized
+/// [`crate::EndHeaderTag`]s are tolerated.
+const fn fixed_tag_size(typ: HeaderTagType) -> Option<usize> {
+    match typ {
+        HeaderTagType::End | HeaderTagType::InformationRequest => None,
+        HeaderTagType::Address => Some(AddressHeaderTag::BASE_SIZE),
+        HeaderTagType::EntryAddress => Some(EntryAddressHeaderTag::BASE_SIZE),
+        HeaderTagType::ConsoleFlags => Some(ConsoleHeaderTag::BASE_SIZE),
+        HeaderTagType::Framebuffer => Some(FramebufferHeaderTag::BASE_SIZE),
+        HeaderTagType::ModuleAlign => Some(ModuleAlignHeaderTag::BASE_SIZE),
+        HeaderTagType::EfiBS => Some(EfiBootServiceHeaderTag::BASE_SIZE),
+        HeaderTagType::EntryAddressEFI32 => Some(EntryEfi32HeaderTag::BASE_SIZE),
+        HeaderTagType::EntryAddressEFI64 => Some(EntryEfi64HeaderTag::BASE_SIZE),
+        HeaderTagType::Relocatable => Some(RelocatableHeaderTag::BASE_SIZE),
+    }
+}
+
 /// Errors that occur when a chunk of memory can't be parsed as
 /// [`Multiboot2Header`].
 #[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum LoadError {
+    /// The length of the header is smaller than the size of the
+    /// [`Multiboot2BasicHeader`].
+    BadBaseLength,
     /// The provided checksum does not match the expected value.
     ChecksumMismatch,
+    /// The header contains more than one [`EntryAddressHeaderTag`], which is
+    /// ambiguous.
+    DuplicateEntryAddress,
+    /// An MBI tag type is requested multiple times. Only returned by
+    /// [`Multiboot2Header::load_checked`].
+    #[display(fmt = "Duplicate information request for {:?}", _0)]
+    DuplicateInfoRequest(MbiTagType),
     /// The header does not contain the correct magic number.
     MagicNotFound,
+    /// The size of an [`InformationRequestHeaderTag`] is not the size of the
+    /// tag header plus a multiple of the size of a request.
+    #[display(fmt = "Malformed information request tag of size {}", size)]
+    MalformedInfoRequest {
+        /// The reported size of the tag.
+        size: u32,
+    },
     /// The provided memory can't be parsed as [`Multiboot2Header`].
     /// See [`MemoryError`].
     Memory(MemoryError),
+    /// The end tag is bigger than 8 bytes. Only returned by
+    /// [`Multiboot2Header::load_checked`].
+    #[display(fmt = "Oversized end tag of size {}", size)]
+    OversizedEndTag {
+        /// The reported size of the tag.
+        size: u32,
+    },
+    /// The architecture of the header is unknown. Only returned by
+    /// [`Multiboot2Header::load_checked`].
+    UnknownArch(UnknownHeaderTagISA),
+    /// The length of the header is zero.
+    ZeroLength,
+}
+
+/// Deviations from the spec that [`Multiboot2Header::load`] tolerates. See
+/// [`Multiboot2Header::load_warnings`].
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LoadWarning {
+    /// The end tag is bigger than 8 bytes, as emitted by some broken tools.
+    /// The tags still end there.
+    #[display(fmt = "Oversized end tag of size {}", size)]
+    OversizedEndTag {
+        /// The reported size of the tag.
+        size: u32,
+    },
+    /// An MBI tag type is requested multiple times, either in the same or in
+    /// different [`InformationRequestHeaderTag`]s. Reported for each
+    /// redundant request.
+    #[display(fmt = "Duplicate information request for {:?}", _0)]
+    DuplicateInfoRequest(MbiTagType),
 }
 
 #[cfg(feature = "unstable")]
@@ -241,11 +1342,39 @@ impl Error for LoadError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             Self::Memory(inner) => Some(inner),
+            Self::UnknownArch(inner) => Some(inner),
             _ => None,
         }
     }
 }
 
+/// Error when the tags of a [`Multiboot2Header`] don't fit into the array of
+/// [`Multiboot2Header::collect_tags`].
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[display(fmt = "The header contains more than {} tags", capacity)]
+pub struct CollectError {
+    /// The capacity of the array.
+    pub capacity: usize,
+}
+
+#[cfg(feature = "unstable")]
+impl Error for CollectError {}
+
+/// Error when [`Multiboot2Header::to_builder_source`] finds a value that the
+/// `Builder` can't express.
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BuilderSourceError {
+    /// The architecture of the header is unknown.
+    #[display(fmt = "{}", _0)]
+    UnknownArch(UnknownHeaderTagISA),
+    /// The preference of the [`RelocatableHeaderTag`] is unknown.
+    #[display(fmt = "{}", _0)]
+    UnknownRelocatableHeaderTagPreference(UnknownRelocatableHeaderTagPreference),
+}
+
+#[cfg(feature = "unstable")]
+impl Error for BuilderSourceError {}
+
 /// The "basic" Multiboot2 header. This means only the properties, that are known during
 /// compile time. All other information are derived during runtime from the size property.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -253,13 +1382,20 @@ impl Error for LoadError {
 pub struct Multiboot2BasicHeader {
     /// Must be the value of [`MAGIC`].
     header_magic: u32,
-    arch: HeaderTagISA,
+    /// Raw value of a [`HeaderTagISA`]. Kept as `u32` so that unknown values
+    /// can be represented.
+    arch: u32,
     length: u32,
     checksum: u32,
     // Followed by dynamic amount of dynamically sized header tags.
     // At minimum, the end tag.
 }
 
+// The tags follow the basic header. This ensures that the first tag is
+// properly aligned.
+const _: () = assert!(size_of::<Multiboot2BasicHeader>() == 16);
+const _: () = assert!(size_of::<Multiboot2BasicHeader>() % ALIGNMENT == 0);
+
 impl Multiboot2BasicHeader {
     #[cfg(feature = "builder")]
     /// Constructor for the basic header.
@@ -268,7 +1404,7 @@ impl Multiboot2BasicHeader {
         let checksum = Self::calc_checksum(magic, arch, length);
         Self {
             header_magic: magic,
-            arch,
+            arch: arch as u32,
             length,
             checksum,
         }
@@ -277,14 +1413,112 @@ impl Multiboot2BasicHeader {
     /// Verifies that a Multiboot2 header is valid.
     #[must_use]
     pub const fn verify_checksum(&self) -> bool {
-        let check = Self::calc_checksum(self.header_magic, self.arch, self.length);
+        let check = Self::calc_checksum_raw(self.header_magic, self.arch, self.length);
         check == self.checksum
     }
 
+    /// Verifies the magic value and the checksum of the raw (native-endian)
+    /// bytes of a header. This is a `const fn`, so that a header that is
+    /// assembled at compile time can be checked at compile time. The tags are
+    /// not validated.
+    ///
+    /// ```
+    /// use multiboot2_header::{EndHeaderTag, HeaderTagISA, Multiboot2BasicHeader, MAGIC};
+    ///
+    /// const fn header(checksum: u32) -> [u8; 24] {
+    ///     let fields = [MAGIC, HeaderTagISA::I386 as u32, 24, checksum];
+    ///     let mut bytes = [0; 24];
+    ///     let mut i = 0;
+    ///     while i < 16 {
+    ///         bytes[i] = fields[i / 4].to_ne_bytes()[i % 4];
+    ///         i += 1;
+    ///     }
+    ///     while i < 24 {
+    ///         bytes[i] = EndHeaderTag::BYTES[i - 16];
+    ///         i += 1;
+    ///     }
+    ///     bytes
+    /// }
+    ///
+    /// const CHECKSUM: u32 = Multiboot2BasicHeader::calc_checksum(MAGIC, HeaderTagISA::I386, 24);
+    /// const _: () = assert!(Multiboot2BasicHeader::verify_bytes(&header(CHECKSUM)));
+    /// ```
+    ///
+    /// A wrong checksum fails the build:
+    ///
+    /// ```compile_fail
+    /// # use multiboot2_header::{HeaderTagISA, Multiboot2BasicHeader, MAGIC};
+    /// # const fn header(checksum: u32) -> [u8; 16] {
+    /// #     let fields = [MAGIC, HeaderTagISA::I386 as u32, 16, checksum];
+    /// #     let mut bytes = [0; 16];
+    /// #     let mut i = 0;
+    /// #     while i < 16 {
+    /// #         bytes[i] = fields[i / 4].to_ne_bytes()[i % 4];
+    /// #         i += 1;
+    /// #     }
+    /// #     bytes
+    /// # }
+    /// const CHECKSUM: u32 = Multiboot2BasicHeader::calc_checksum(MAGIC, HeaderTagISA::I386, 16);
+    /// const _: () = assert!(Multiboot2BasicHeader::verify_bytes(&header(CHECKSUM + 1)));
+    /// ```
+    #[must_use]
+    pub const fn verify_bytes(bytes: &[u8]) -> bool {
+        const fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+            u32::from_ne_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ])
+        }
+
+        if bytes.len() < size_of::<Self>() {
+            return false;
+        }
+        let magic = read_u32(bytes, 0);
+        let arch = read_u32(bytes, 4);
+        let length = read_u32(bytes, 8);
+        let checksum = read_u32(bytes, 12);
+        magic == MAGIC
+            && magic
+                .wrapping_add(arch)
+                .wrapping_add(length)
+                .wrapping_add(checksum)
+                == 0
+    }
+
     /// Calculates the checksum as described in the spec.
     #[must_use]
     pub const fn calc_checksum(magic: u32, arch: HeaderTagISA, length: u32) -> u32 {
-        (0x100000000 - magic as u64 - arch as u64 - length as u64) as u32
+        Self::calc_checksum_raw(magic, arch as u32, length)
+    }
+
+    /// Like [`Self::calc_checksum`] but additionally returns the intermediate
+    /// steps of the calculation. This is useful for tooling that visualizes
+    /// or debugs the checksum.
+    #[must_use]
+    pub const fn calc_checksum_verbose(
+        magic: u32,
+        arch: HeaderTagISA,
+        length: u32,
+    ) -> (u32, ChecksumSteps) {
+        let magic_plus_arch = magic.wrapping_add(arch as u32);
+        let sum = magic_plus_arch.wrapping_add(length);
+        let checksum = 0_u32.wrapping_sub(sum);
+        let steps = ChecksumSteps {
+            magic_plus_arch,
+            sum,
+            checksum,
+        };
+        (checksum, steps)
+    }
+
+    /// Like [`Self::calc_checksum`] but also works for unknown architectures.
+    const fn calc_checksum_raw(magic: u32, arch: u32, length: u32) -> u32 {
+        0_u32
+            .wrapping_sub(magic)
+            .wrapping_sub(arch)
+            .wrapping_sub(length)
     }
 
     /// Returns the header magic.
@@ -293,9 +1527,16 @@ impl Multiboot2BasicHeader {
         self.header_magic
     }
 
-    /// Returns the [`HeaderTagISA`].
+    /// Returns the [`HeaderTagISA`] or an error, if the architecture is
+    /// unknown. See [`Self::arch_raw`].
+    pub const fn arch(&self) -> Result<HeaderTagISA, UnknownHeaderTagISA> {
+        HeaderTagISA::from_u32(self.arch)
+    }
+
+    /// Returns the raw value of the architecture, which might not be a known
+    /// [`HeaderTagISA`].
     #[must_use]
-    pub const fn arch(&self) -> HeaderTagISA {
+    pub const fn arch_raw(&self) -> u32 {
         self.arch
     }
 
@@ -319,7 +1560,7 @@ impl Header for Multiboot2BasicHeader {
 
     fn set_size(&mut self, total_size: usize) {
         self.length = total_size as u32;
-        self.checksum = Self::calc_checksum(self.header_magic, self.arch, total_size as u32);
+        self.checksum = Self::calc_checksum_raw(self.header_magic, self.arch, total_size as u32);
     }
 }
 
@@ -327,7 +1568,7 @@ impl Debug for Multiboot2BasicHeader {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Multiboot2Header")
             .field("header_magic", &{ self.header_magic })
-            .field("arch", &{ self.arch })
+            .field("arch", &self.arch())
             .field("length", &{ self.length })
             .field("checksum", &{ self.checksum })
             //.field("tags", &self.iter())
@@ -337,10 +1578,1699 @@ impl Debug for Multiboot2BasicHeader {
 
 #[cfg(test)]
 mod tests {
-    use crate::Multiboot2BasicHeader;
+    use crate::{
+        EfiBootServiceHeaderTag, EndHeaderTag, HeaderTagFlag, HeaderTagHeader, HeaderTagISA,
+        HeaderTagType, HeaderTagVisitor, LoadError, LoadWarning, MbiTagType, ModuleAlignHeaderTag,
+        Multiboot2BasicHeader, Multiboot2Header, ParsedHeaderTag, RelocatableHeaderTag,
+        UnknownHeaderTagISA, MAGIC,
+    };
+    use multiboot2_common::test_utils::AlignedBytes;
+    use multiboot2_common::{DynSizedStructure, MemoryError};
 
     #[test]
     fn test_assert_size() {
         assert_eq!(core::mem::size_of::<Multiboot2BasicHeader>(), 4 + 4 + 4 + 4);
     }
+
+    #[test]
+    fn test_calc_checksum_verbose() {
+        let (checksum, steps) =
+            Multiboot2BasicHeader::calc_checksum_verbose(MAGIC, HeaderTagISA::MIPS32, 24);
+        assert_eq!(
+            checksum,
+            Multiboot2BasicHeader::calc_checksum(MAGIC, HeaderTagISA::MIPS32, 24)
+        );
+        assert_eq!(steps.magic_plus_arch, MAGIC + 4);
+        assert_eq!(steps.sum, MAGIC + 4 + 24);
+        assert_eq!(steps.checksum, checksum);
+        assert_eq!(steps.sum.wrapping_add(checksum), 0);
+    }
+
+    #[test]
+    fn test_load_bad_base_length() {
+        #[rustfmt::skip]
+        let bytes = AlignedBytes::new([
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            8, 0, 0, 0,
+            /* checksum */
+            0x22, 0xaf, 0xad, 0x17,
+        ]);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) };
+        assert_eq!(header.unwrap_err(), LoadError::BadBaseLength);
+    }
+
+    #[test]
+    fn test_load_zero_length() {
+        #[rustfmt::skip]
+        let raw = [
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            0, 0, 0, 0,
+            /* checksum */
+            0x2a, 0xaf, 0xad, 0x17,
+        ];
+        let bytes = AlignedBytes::new(raw);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) };
+        assert_eq!(header.unwrap_err(), LoadError::ZeroLength);
+
+        let mut buffer = AlignedBytes::new([0; 8192]);
+        buffer.0[..raw.len()].copy_from_slice(&raw);
+        assert_eq!(
+            Multiboot2Header::find_header(&buffer[..]),
+            Err(LoadError::ZeroLength)
+        );
+    }
+
+    #[test]
+    fn test_load_checked() {
+        #[rustfmt::skip]
+        let mut raw = [
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            24, 0, 0, 0,
+            /* checksum */
+            0x12, 0xaf, 0xad, 0x17,
+            /* end tag */
+            0, 0, 0, 0,
+            8, 0, 0, 0,
+        ];
+        let bytes = AlignedBytes::new(raw);
+        let header = Multiboot2Header::load_checked(&bytes.0).unwrap();
+        assert_eq!(header.arch(), Ok(HeaderTagISA::I386));
+
+        // Unknown architecture 7 with an updated checksum.
+        raw[4] = 7;
+        raw[12] = 0x0b;
+        let bytes = AlignedBytes::new(raw);
+        assert!(unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) }.is_ok());
+        let header = Multiboot2Header::load_checked(&bytes.0);
+        assert_eq!(
+            header.unwrap_err(),
+            LoadError::UnknownArch(UnknownHeaderTagISA(7))
+        );
+
+        raw[0] = 0;
+        let bytes = AlignedBytes::new(raw);
+        let header = Multiboot2Header::load_checked(&bytes.0);
+        assert_eq!(header.unwrap_err(), LoadError::MagicNotFound);
+
+        // Truncated and misaligned headers are rejected.
+        raw[0] = 0xd6;
+        raw[4] = 0;
+        raw[12] = 0x12;
+        let bytes = AlignedBytes::new(raw);
+        assert!(Multiboot2Header::load_checked(&bytes.0).is_ok());
+        assert_eq!(
+            Multiboot2Header::load_checked(&bytes.0[..16]).unwrap_err(),
+            LoadError::Memory(MemoryError::InvalidReportedTotalSize)
+        );
+        let mut misaligned = AlignedBytes([0; 28]);
+        misaligned.0[4..].copy_from_slice(&raw);
+        assert_eq!(
+            Multiboot2Header::load_checked(&misaligned.0[4..]).unwrap_err(),
+            LoadError::Memory(MemoryError::WrongAlignment)
+        );
+    }
+
+    #[test]
+    fn test_load_checked_bad_tag_size() {
+        let relocatable = HeaderTagType::Relocatable as u16;
+        for (typ, size) in [(0x99, 0), (0x99, 64), (relocatable, 16)] {
+            let bytes = header_with_tag(typ, size);
+            assert_eq!(
+                Multiboot2Header::load_checked(&bytes.0).unwrap_err(),
+                LoadError::Memory(MemoryError::InvalidReportedTotalSize)
+            );
+            assert_eq!(
+                Multiboot2Header::first_error(&bytes.0),
+                Some((16, LoadError::Memory(MemoryError::InvalidReportedTotalSize)))
+            );
+        }
+
+        // Tags of a fixed size must have exactly this size.
+        let entry_address = HeaderTagType::EntryAddress as u16;
+        let bytes = header_with_tag(entry_address, 12);
+        assert!(Multiboot2Header::load_checked(&bytes.0).is_ok());
+        let bytes = header_with_tag(entry_address, 16);
+        assert_eq!(
+            Multiboot2Header::load_checked(&bytes.0).unwrap_err(),
+            LoadError::Memory(MemoryError::InvalidReportedTotalSize)
+        );
+    }
+
+    #[test]
+    fn test_tags() {
+        #[rustfmt::skip]
+        let raw = [
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            48, 0, 0, 0,
+            /* checksum */
+            0xfa, 0xae, 0xad, 0x17,
+            /* tag of unknown type 0x1337 with size 12 */
+            0x37, 0x13, 0, 0,
+            12, 0, 0, 0,
+            0xaa, 0xbb, 0xcc, 0xdd,
+            /* padding */
+            0, 0, 0, 0,
+            /* end tag */
+            0, 0, 0, 0,
+            8, 0, 0, 0,
+            /* module align tag after the end tag */
+            6, 0, 0, 0,
+            8, 0, 0, 0,
+        ];
+        let bytes = AlignedBytes::new(raw);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) }.unwrap();
+        assert_eq!(header.iter().count(), 2);
+        assert!(header.module_align_tag().is_none());
+
+        let mut tags = header.tags();
+        let Some(ParsedHeaderTag::Unknown(tag)) = tags.next() else {
+            panic!("expected unknown tag");
+        };
+        assert_eq!(tag.header().typ_raw(), 0x1337);
+        assert_eq!(tag.payload(), &[0xaa, 0xbb, 0xcc, 0xdd]);
+        assert!(matches!(tags.next(), Some(ParsedHeaderTag::End(_))));
+        assert!(tags.next().is_none());
+    }
+
+    #[test]
+    fn test_bytes_after_end_tag() {
+        #[derive(Default)]
+        struct CountingVisitor(usize);
+
+        impl HeaderTagVisitor for CountingVisitor {
+            fn visit_end(&mut self, _tag: &EndHeaderTag) {
+                self.0 += 1;
+            }
+            fn visit_unknown(&mut self, _tag: &DynSizedStructure<HeaderTagHeader>) {
+                self.0 += 1;
+            }
+        }
+
+        // Zeroed bytes after the end tag look like end tags of size 0.
+        let mut bytes = AlignedBytes::new([0; 48]);
+        let checksum = Multiboot2Header::calc_checksum(MAGIC, HeaderTagISA::I386, 48);
+        bytes.0[0..4].copy_from_slice(&MAGIC.to_ne_bytes());
+        bytes.0[8..12].copy_from_slice(&48_u32.to_ne_bytes());
+        bytes.0[12..16].copy_from_slice(&checksum.to_ne_bytes());
+        bytes.0[16..18].copy_from_slice(&0x99_u16.to_ne_bytes());
+        bytes.0[20..24].copy_from_slice(&8_u32.to_ne_bytes());
+        bytes.0[24..32].copy_from_slice(&EndHeaderTag::BYTES);
+        let header = Multiboot2Header::load_checked(&bytes.0).unwrap();
+
+        assert_eq!(header.iter().count(), 2);
+        let mut visitor = CountingVisitor::default();
+        header.visit(&mut visitor);
+        assert_eq!(visitor.0, 2);
+        assert_eq!(header.supported_tags(&[HeaderTagType::End]).count(), 1);
+        #[cfg(feature = "alloc")]
+        assert_eq!(header.partition_tags().0.len(), 2);
+    }
+
+    #[test]
+    fn test_length_discrepancy() {
+        #[rustfmt::skip]
+        let raw = [
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            24, 0, 0, 0,
+            /* checksum */
+            0x12, 0xaf, 0xad, 0x17,
+            /* end tag */
+            0, 0, 0, 0,
+            8, 0, 0, 0,
+        ];
+        let bytes = AlignedBytes::new(raw);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) }.unwrap();
+        assert_eq!(header.length_discrepancy(), 0);
+
+        #[rustfmt::skip]
+        let raw = [
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            40, 0, 0, 0,
+            /* checksum */
+            0x02, 0xaf, 0xad, 0x17,
+            /* end tag */
+            0, 0, 0, 0,
+            8, 0, 0, 0,
+            /* two module align tags after the end tag */
+            6, 0, 0, 0,
+            8, 0, 0, 0,
+            6, 0, 0, 0,
+            8, 0, 0, 0,
+        ];
+        let bytes = AlignedBytes::new(raw);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) }.unwrap();
+        assert_eq!(header.length_discrepancy(), 16);
+    }
+
+    #[test]
+    fn test_oversized_end_tag() {
+        #[rustfmt::skip]
+        let raw = [
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            32, 0, 0, 0,
+            /* checksum */
+            0x0a, 0xaf, 0xad, 0x17,
+            /* end tag of size 16 */
+            0, 0, 0, 0,
+            16, 0, 0, 0,
+            0, 0, 0, 0,
+            0, 0, 0, 0,
+        ];
+        let bytes = AlignedBytes::new(raw);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) }.unwrap();
+        let mut tags = header.tags();
+        assert!(matches!(tags.next(), Some(ParsedHeaderTag::End(tag)) if tag.size() == 16));
+        assert!(tags.next().is_none());
+        assert_eq!(
+            header.load_warnings().collect::<std::vec::Vec<_>>(),
+            [LoadWarning::OversizedEndTag { size: 16 }]
+        );
+
+        let header = Multiboot2Header::load_checked(&bytes.0);
+        assert_eq!(header.unwrap_err(), LoadError::OversizedEndTag { size: 16 });
+    }
+
+    #[test]
+    fn test_duplicate_info_request() {
+        #[rustfmt::skip]
+        let raw = [
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            56, 0, 0, 0,
+            /* checksum */
+            0xf2, 0xae, 0xad, 0x17,
+            /* information request tag for cmdline */
+            1, 0, 0, 0,
+            12, 0, 0, 0,
+            1, 0, 0, 0,
+            0, 0, 0, 0,
+            /* information request tag for mmap and cmdline */
+            1, 0, 0, 0,
+            16, 0, 0, 0,
+            6, 0, 0, 0,
+            1, 0, 0, 0,
+            /* end tag */
+            0, 0, 0, 0,
+            8, 0, 0, 0,
+        ];
+        let bytes = AlignedBytes::new(raw);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) }.unwrap();
+        assert_eq!(
+            header.load_warnings().collect::<std::vec::Vec<_>>(),
+            [LoadWarning::DuplicateInfoRequest(MbiTagType::Cmdline)]
+        );
+
+        let header = Multiboot2Header::load_checked(&bytes.0);
+        assert_eq!(
+            header.unwrap_err(),
+            LoadError::DuplicateInfoRequest(MbiTagType::Cmdline)
+        );
+    }
+
+    #[test]
+    fn test_first_error() {
+        #[rustfmt::skip]
+        let mut raw = [
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            40, 0, 0, 0,
+            /* checksum */
+            0x02, 0xaf, 0xad, 0x17,
+            /* information request tag of size 12 */
+            1, 0, 0, 0,
+            12, 0, 0, 0,
+            4, 0, 0, 0,
+            0, 0, 0, 0,
+            /* end tag */
+            0, 0, 0, 0,
+            8, 0, 0, 0,
+        ];
+        assert_eq!(Multiboot2Header::first_error(&raw), None);
+
+        // Corrupt the size of the information request tag.
+        raw[20] = 10;
+        assert_eq!(
+            Multiboot2Header::first_error(&raw),
+            Some((16, LoadError::MalformedInfoRequest { size: 10 }))
+        );
+
+        // The tag exceeds the header.
+        raw[20] = 64;
+        assert_eq!(
+            Multiboot2Header::first_error(&raw),
+            Some((16, LoadError::Memory(MemoryError::InvalidReportedTotalSize)))
+        );
+
+        raw[12] = 0;
+        assert_eq!(
+            Multiboot2Header::first_error(&raw),
+            Some((12, LoadError::ChecksumMismatch))
+        );
+        assert_eq!(
+            Multiboot2Header::first_error(&raw[..8]),
+            Some((0, LoadError::Memory(MemoryError::ShorterThanHeader)))
+        );
+    }
+
+    /// Returns a header of 40 bytes with a tag of the raw type `typ` that
+    /// spans 16 bytes but reports `size`, followed by the end tag.
+    fn header_with_tag(typ: u16, size: u32) -> AlignedBytes<40> {
+        let mut bytes = AlignedBytes::new([0; 40]);
+        let checksum = Multiboot2Header::calc_checksum(MAGIC, HeaderTagISA::I386, 40);
+        bytes.0[0..4].copy_from_slice(&MAGIC.to_ne_bytes());
+        bytes.0[8..12].copy_from_slice(&40_u32.to_ne_bytes());
+        bytes.0[12..16].copy_from_slice(&checksum.to_ne_bytes());
+        bytes.0[16..18].copy_from_slice(&typ.to_ne_bytes());
+        bytes.0[20..24].copy_from_slice(&size.to_ne_bytes());
+        bytes.0[32..40].copy_from_slice(&EndHeaderTag::BYTES);
+        bytes
+    }
+
+    #[test]
+    fn test_first_error_matches_load() {
+        for (size, expected) in [
+            (16, None),
+            (
+                0,
+                Some(LoadError::Memory(MemoryError::InvalidReportedTotalSize)),
+            ),
+            (
+                64,
+                Some(LoadError::Memory(MemoryError::InvalidReportedTotalSize)),
+            ),
+        ] {
+            let bytes = header_with_tag(0x99, size);
+            let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) };
+            assert_eq!(header.err(), expected);
+            assert_eq!(
+                Multiboot2Header::first_error(&bytes.0),
+                expected.map(|err| (16, err))
+            );
+        }
+    }
+
+    #[test]
+    fn test_load_info_request_bad_size() {
+        for size in [0, 4, 64] {
+            let bytes = header_with_tag(HeaderTagType::InformationRequest as u16, size);
+            let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) };
+            assert_eq!(
+                header.unwrap_err(),
+                LoadError::Memory(MemoryError::InvalidReportedTotalSize)
+            );
+        }
+    }
+
+    #[test]
+    fn test_load_malformed_info_request() {
+        #[rustfmt::skip]
+        let bytes = AlignedBytes::new([
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            40, 0, 0, 0,
+            /* checksum */
+            0x02, 0xaf, 0xad, 0x17,
+            /* information request tag with size 11 */
+            1, 0, 0, 0,
+            11, 0, 0, 0,
+            1, 0, 0,
+            /* padding */
+            0, 0, 0, 0, 0,
+            /* end tag */
+            0, 0, 0, 0,
+            8, 0, 0, 0,
+        ]);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) };
+        assert_eq!(
+            header.unwrap_err(),
+            LoadError::MalformedInfoRequest { size: 11 }
+        );
+    }
+
+    #[test]
+    fn test_visit() {
+        #[derive(Default)]
+        struct CountingVisitor {
+            end: usize,
+            module_align: usize,
+            efi_bs: usize,
+            relocatable: usize,
+            unknown: usize,
+        }
+
+        impl HeaderTagVisitor for CountingVisitor {
+            fn visit_end(&mut self, _tag: &EndHeaderTag) {
+                self.end += 1;
+            }
+
+            fn visit_module_align(&mut self, _tag: &ModuleAlignHeaderTag) {
+                self.module_align += 1;
+            }
+
+            fn visit_efi_bs(&mut self, _tag: &EfiBootServiceHeaderTag) {
+                self.efi_bs += 1;
+            }
+
+            fn visit_relocatable(&mut self, _tag: &RelocatableHeaderTag) {
+                self.relocatable += 1;
+            }
+
+            fn visit_unknown(&mut self, tag: &DynSizedStructure<HeaderTagHeader>) {
+                assert_eq!(tag.header().typ_raw(), 42);
+                self.unknown += 1;
+            }
+        }
+
+        #[rustfmt::skip]
+        let bytes = AlignedBytes::new([
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            56, 0, 0, 0,
+            /* checksum */
+            0xf2, 0xae, 0xad, 0x17,
+            /* module align tag */
+            6, 0, 0, 0,
+            8, 0, 0, 0,
+            /* tag of unknown type 42 */
+            42, 0, 1, 0,
+            12, 0, 0, 0,
+            1, 2, 3, 4,
+            /* padding */
+            0, 0, 0, 0,
+            /* efi boot services tag */
+            7, 0, 0, 0,
+            8, 0, 0, 0,
+            /* end tag */
+            0, 0, 0, 0,
+            8, 0, 0, 0,
+        ]);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) }.unwrap();
+
+        let mut visitor = CountingVisitor::default();
+        header.visit(&mut visitor);
+        assert_eq!(visitor.end, 1);
+        assert_eq!(visitor.module_align, 1);
+        assert_eq!(visitor.efi_bs, 1);
+        assert_eq!(visitor.relocatable, 0);
+        assert_eq!(visitor.unknown, 1);
+    }
+
+    #[test]
+    fn test_has_reserved_flag_bits() {
+        #[rustfmt::skip]
+        let mut raw = [
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            32, 0, 0, 0,
+            /* checksum */
+            0x0a, 0xaf, 0xad, 0x17,
+            /* module align tag */
+            6, 0, 1, 0,
+            8, 0, 0, 0,
+            /* end tag */
+            0, 0, 0, 0,
+            8, 0, 0, 0,
+        ];
+        let bytes = AlignedBytes::new(raw);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) }.unwrap();
+        assert!(!header.has_reserved_flag_bits());
+
+        // Set bit 2 of the flags of the module align tag.
+        raw[18] |= 0b100;
+        let bytes = AlignedBytes::new(raw);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) }.unwrap();
+        assert!(header.has_reserved_flag_bits());
+        let tag = header.module_align_tag().unwrap();
+        assert_eq!(tag.flags(), HeaderTagFlag::Optional);
+    }
+
+    #[test]
+    fn test_tag_region_crc32() {
+        #[rustfmt::skip]
+        let mut raw = [
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            32, 0, 0, 0,
+            /* checksum */
+            0x0a, 0xaf, 0xad, 0x17,
+            /* module align tag */
+            6, 0, 0, 0,
+            8, 0, 0, 0,
+            /* end tag */
+            0, 0, 0, 0,
+            8, 0, 0, 0,
+        ];
+        let bytes = AlignedBytes::new(raw);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) }.unwrap();
+        let crc = header.tag_region_crc32();
+        assert_eq!(crc, header.tag_region_crc32());
+
+        // Mark the module align tag as optional.
+        raw[18] = 1;
+        let bytes = AlignedBytes::new(raw);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) }.unwrap();
+        assert_ne!(crc, header.tag_region_crc32());
+    }
+
+    #[test]
+    fn test_unknown_tag_types() {
+        #[rustfmt::skip]
+        let bytes = AlignedBytes::new([
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            40, 0, 0, 0,
+            /* checksum */
+            0x02, 0xaf, 0xad, 0x17,
+            /* tag of unknown type 42 */
+            42, 0, 1, 0,
+            8, 0, 0, 0,
+            /* tag of unknown type 256 */
+            0, 1, 1, 0,
+            8, 0, 0, 0,
+            /* end tag */
+            0, 0, 0, 0,
+            8, 0, 0, 0,
+        ]);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) }.unwrap();
+        let mut unknown = header.unknown_tag_types();
+        assert_eq!(unknown.next(), Some(42));
+        assert_eq!(unknown.next(), Some(256));
+        assert_eq!(unknown.next(), None);
+    }
+
+    #[test]
+    fn test_load_duplicate_entry_address() {
+        #[rustfmt::skip]
+        let bytes = AlignedBytes::new([
+            /* magic */
+            0xd6, 0x50, 0x52, 0xe8,
+            /* arch */
+            0, 0, 0, 0,
+            /* length */
+            56, 0, 0, 0,
+            /* checksum */
+            0xf2, 0xae, 0xad, 0x17,
+            /* entry address tag */
+            3, 0, 0, 0,
+            12, 0, 0, 0,
+            0, 0x10, 0, 0,
+            /* padding */
+            0, 0, 0, 0,
+            /* entry address tag */
+            3, 0, 0, 0,
+            12, 0, 0, 0,
+            0, 0x20, 0, 0,
+            /* padding */
+            0, 0, 0, 0,
+            /* end tag */
+            0, 0, 0, 0,
+            8, 0, 0, 0,
+        ]);
+        let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) };
+        assert_eq!(header.unwrap_err(), LoadError::DuplicateEntryAddress);
+    }
+
+    #[cfg(feature = "builder")]
+    mod builder {
+        use crate::{
+            AddressHeaderTag, BaseHeader, BootContext, Builder, BuilderSourceError, CollectError,
+            ConsoleHeaderTag, ConsoleHeaderTagFlags, DisplayPreference, EfiBootServiceHeaderTag,
+            EfiTagSummary, EndHeaderTag, EntryAddressHeaderTag, EntryEfi32HeaderTag,
+            EntryEfi64HeaderTag, EntryPoint, FramebufferHeaderTag, HeaderTagFlag, HeaderTagISA,
+            HeaderTagType, InformationRequestHeaderTag, LoadError, LoaderPlan, MbiTagType,
+            ModuleAlignHeaderTag, Multiboot2BasicHeader, Multiboot2Header, OwnedMultiboot2Header,
+            RelocatableHeaderTag, RelocatableHeaderTagPreference, UnknownHeaderTagISA, MAGIC,
+        };
+        use core::mem::size_of;
+        use multiboot2_common::test_utils::AlignedBytes;
+        use multiboot2_common::{
+            increase_to_alignment, DynSizedStructure, MaybeDynSized, MemoryError,
+        };
+        use std::vec::Vec;
+
+        fn load(structure: &DynSizedStructure<Multiboot2BasicHeader>) -> Multiboot2Header<'_> {
+            unsafe { Multiboot2Header::load(structure.as_bytes().as_ptr().cast()) }.unwrap()
+        }
+
+        fn relocatable_tag(min_addr: u32, max_addr: u32) -> RelocatableHeaderTag {
+            RelocatableHeaderTag::new(
+                HeaderTagFlag::Required,
+                min_addr,
+                max_addr,
+                4096,
+                RelocatableHeaderTagPreference::None,
+            )
+        }
+
+        #[test]
+        fn test_supported_tags() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .console_tag(ConsoleHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    ConsoleHeaderTagFlags::ConsoleRequired,
+                ))
+                .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Optional))
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let header = load(&structure);
+
+            let supported = [HeaderTagType::ModuleAlign, HeaderTagType::Relocatable];
+            let supported_tags = header
+                .supported_tags(&supported)
+                .map(|tag| tag.typ().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                supported_tags,
+                [HeaderTagType::ModuleAlign, HeaderTagType::Relocatable]
+            );
+
+            let unsupported = header
+                .unsupported_required_tags(&supported)
+                .map(|tag| tag.typ().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(unsupported, [HeaderTagType::ConsoleFlags]);
+        }
+
+        #[test]
+        fn test_partition_tags() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .console_tag(ConsoleHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    ConsoleHeaderTagFlags::ConsoleRequired,
+                ))
+                .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Optional))
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let header = load(&structure);
+
+            let (required, optional) = header.partition_tags();
+            let required = required
+                .iter()
+                .map(|tag| tag.typ().unwrap())
+                .collect::<Vec<_>>();
+            let optional = optional
+                .iter()
+                .map(|tag| tag.typ().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                required,
+                [
+                    HeaderTagType::ConsoleFlags,
+                    HeaderTagType::Relocatable,
+                    HeaderTagType::End
+                ]
+            );
+            assert_eq!(optional, [HeaderTagType::ModuleAlign]);
+        }
+
+        #[test]
+        fn test_address_ranges_consistent() {
+            let address_tag =
+                AddressHeaderTag::new(HeaderTagFlag::Required, 0x2000, 0x2000, 0x3000, 0x4000);
+
+            // Only one of both tags.
+            let structure = Builder::new(HeaderTagISA::I386)
+                .address_tag(address_tag)
+                .build();
+            assert!(load(&structure).address_ranges_consistent());
+
+            let structure = Builder::new(HeaderTagISA::I386)
+                .address_tag(address_tag)
+                .relocatable_tag(relocatable_tag(0x1000, 0x4000))
+                .build();
+            assert!(load(&structure).address_ranges_consistent());
+
+            // bss end exceeds the max address
+            let structure = Builder::new(HeaderTagISA::I386)
+                .address_tag(address_tag)
+                .relocatable_tag(relocatable_tag(0x1000, 0x3fff))
+                .build();
+            assert!(!load(&structure).address_ranges_consistent());
+
+            // load address below the min address
+            let structure = Builder::new(HeaderTagISA::I386)
+                .address_tag(address_tag)
+                .relocatable_tag(relocatable_tag(0x2001, 0x4000))
+                .build();
+            assert!(!load(&structure).address_ranges_consistent());
+        }
+
+        #[test]
+        fn test_tag_summaries() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .console_tag(ConsoleHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    ConsoleHeaderTagFlags::ConsoleRequired,
+                ))
+                .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Optional))
+                .relocatable_tag(relocatable_tag(0x1000, 0x8000))
+                .vendor_tag(0x1337, HeaderTagFlag::Optional, &[])
+                .build();
+            let header = load(&structure);
+            assert_eq!(
+                header.tag_summaries().collect::<Vec<_>>(),
+                [
+                    (HeaderTagType::ConsoleFlags, HeaderTagFlag::Required, 12),
+                    (HeaderTagType::ModuleAlign, HeaderTagFlag::Optional, 8),
+                    (HeaderTagType::Relocatable, HeaderTagFlag::Required, 24),
+                    (HeaderTagType::End, HeaderTagFlag::Required, 8),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_padding_bytes() {
+            // All tags are a multiple of 8 bytes.
+            let structure = Builder::new(HeaderTagISA::I386)
+                .relocatable_tag(relocatable_tag(0x1000, 0x8000))
+                .build();
+            assert_eq!(load(&structure).padding_bytes(), 0);
+
+            // The console and the information request tag are 12 bytes each.
+            let structure = Builder::new(HeaderTagISA::I386)
+                .information_request_tag(InformationRequestHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    &[MbiTagType::Cmdline.into()],
+                ))
+                .console_tag(ConsoleHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    ConsoleHeaderTagFlags::ConsoleRequired,
+                ))
+                .relocatable_tag(relocatable_tag(0x1000, 0x8000))
+                .build();
+            let header = load(&structure);
+            assert_eq!(header.padding_bytes(), 4 + 4);
+            assert_eq!(header.length(), 16 + 16 + 16 + 24 + 8);
+        }
+
+        #[test]
+        fn test_all_addresses_below() {
+            let builder = || {
+                Builder::new(HeaderTagISA::I386)
+                    .address_tag(AddressHeaderTag::new(
+                        HeaderTagFlag::Required,
+                        0x2000,
+                        0x2000,
+                        0x3000,
+                        0x4000,
+                    ))
+                    .entry_tag(EntryAddressHeaderTag::new(HeaderTagFlag::Required, 0x2000))
+                    .relocatable_tag(relocatable_tag(0x1000, 0x8000))
+            };
+            let structure = builder().build();
+            assert!(load(&structure).all_addresses_below(0x8001));
+            // the max address of the relocatable tag exceeds the ceiling
+            assert!(!load(&structure).all_addresses_below(0x8000));
+
+            // the EFI entry address exceeds the ceiling
+            let structure = builder()
+                .efi_64_tag(EntryEfi64HeaderTag::new(HeaderTagFlag::Required, 0x1_0000))
+                .build();
+            assert!(!load(&structure).all_addresses_below(0x8001));
+        }
+
+        #[test]
+        fn test_requests_framebuffer() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            assert!(!load(&structure).requests_framebuffer());
+
+            let structure = Builder::new(HeaderTagISA::I386)
+                .framebuffer_tag(FramebufferHeaderTag::new(
+                    HeaderTagFlag::Optional,
+                    1024,
+                    768,
+                    32,
+                ))
+                .build();
+            assert!(load(&structure).requests_framebuffer());
+        }
+
+        #[test]
+        fn test_find_in_realigned() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let mut buffer = vec![0xff_u8; 3];
+            buffer.extend_from_slice(&structure.as_bytes());
+            buffer.extend_from_slice(&[0; 13]);
+
+            let (offset, owned) = Multiboot2Header::find_in_realigned(&buffer).unwrap();
+            assert_eq!(offset, 3);
+            let header = owned.header();
+            assert!(header.verify_checksum());
+            assert_eq!(header.length(), load(&structure).length());
+            assert_eq!(
+                header.relocatable_tag(),
+                Some(&relocatable_tag(0x1000, 0x10000))
+            );
+
+            assert!(Multiboot2Header::find_in_realigned(&buffer[4..]).is_none());
+
+            // The tags are validated: The relocatable tag becomes a malformed
+            // information request.
+            buffer[3 + 16..3 + 18].copy_from_slice(&1_u16.to_ne_bytes());
+            buffer[3 + 20..3 + 24].copy_from_slice(&22_u32.to_ne_bytes());
+            assert!(Multiboot2Header::find_in_realigned(&buffer).is_none());
+
+            // Tags of a bad size don't panic.
+            let relocatable = HeaderTagType::Relocatable as u16;
+            for (typ, size) in [(0x99, 0), (0x99, 64), (relocatable, 16)] {
+                let mut buffer = vec![0xff_u8; 5];
+                buffer.extend_from_slice(&super::header_with_tag(typ, size).0);
+                assert!(Multiboot2Header::find_in_realigned(&buffer).is_none());
+            }
+        }
+
+        #[test]
+        fn test_find() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let header_bytes = structure.as_bytes();
+            let mut raw = AlignedBytes([0xff_u8; 256]);
+            raw.0[16..16 + header_bytes.len()].copy_from_slice(&header_bytes);
+
+            let (offset, header) = Multiboot2Header::find(&raw.0).unwrap();
+            assert_eq!(offset, 16);
+            assert_eq!(header.length(), load(&structure).length());
+            assert_eq!(
+                header.relocatable_tag(),
+                Some(&relocatable_tag(0x1000, 0x10000))
+            );
+
+            // Truncated header at the tail of the slice.
+            assert!(Multiboot2Header::find(&raw.0[..16 + header_bytes.len() - 1]).is_none());
+
+            // Not 8-byte aligned.
+            let mut raw = AlignedBytes([0xff_u8; 256]);
+            raw.0[20..20 + header_bytes.len()].copy_from_slice(&header_bytes);
+            assert!(Multiboot2Header::find(&raw.0).is_none());
+
+            // Invalid checksum.
+            let mut raw = AlignedBytes([0xff_u8; 256]);
+            raw.0[..header_bytes.len()].copy_from_slice(&header_bytes);
+            raw.0[12] ^= 1;
+            assert!(Multiboot2Header::find(&raw.0).is_none());
+
+            // Tags of a bad size, as reported by `first_error`.
+            let relocatable = HeaderTagType::Relocatable as u16;
+            for (typ, size) in [(0x99, 0), (0x99, 64), (relocatable, 16)] {
+                let bytes = super::header_with_tag(typ, size);
+                assert!(Multiboot2Header::first_error(&bytes.0).is_some());
+                assert!(Multiboot2Header::find(&bytes.0).is_none());
+            }
+        }
+
+        #[test]
+        fn test_first_32k() {
+            assert!(Multiboot2Header::is_in_first_32k(0));
+            assert!(Multiboot2Header::is_in_first_32k(32760));
+            assert!(!Multiboot2Header::is_in_first_32k(40000));
+
+            let structure = Builder::new(HeaderTagISA::I386).build();
+            let header = load(&structure);
+            assert!(header.fits_in_first_32k(0));
+            assert!(header.fits_in_first_32k(32768 - 24));
+            assert!(!header.fits_in_first_32k(32760));
+            assert!(!header.fits_in_first_32k(40000));
+        }
+
+        #[test]
+        fn test_as_bytes() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let header = load(&structure);
+            let bytes = header.as_bytes();
+            assert_eq!(bytes.len(), header.length() as usize);
+            assert_eq!(bytes, *structure.as_bytes());
+            assert_eq!(&bytes[0..4], &MAGIC.to_le_bytes());
+        }
+
+        #[test]
+        fn test_tag_objects() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .information_request_tag(InformationRequestHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    &[MbiTagType::Cmdline.into()],
+                ))
+                .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Optional))
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .vendor_tag(0x1337, HeaderTagFlag::Optional, &[])
+                .build();
+            let header = load(&structure);
+            // The information request and the vendor tag are skipped.
+            let objects = header.tag_objects();
+            let sizes = objects.iter().map(|tag| tag.size()).collect::<Vec<_>>();
+            assert_eq!(sizes, [8, 24, 8]);
+            let types = objects.iter().map(|tag| tag.typ_raw()).collect::<Vec<_>>();
+            assert_eq!(
+                types,
+                [
+                    HeaderTagType::ModuleAlign as u16,
+                    HeaderTagType::Relocatable as u16,
+                    HeaderTagType::End as u16
+                ]
+            );
+            assert_eq!(objects[1].flags(), HeaderTagFlag::Required);
+        }
+
+        #[test]
+        fn test_get_tag() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Optional))
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let header = load(&structure);
+            assert_eq!(
+                header.get_tag::<RelocatableHeaderTag>(),
+                Some(&relocatable_tag(0x1000, 0x10000))
+            );
+            assert_eq!(
+                header.get_tag::<ModuleAlignHeaderTag>(),
+                Some(&ModuleAlignHeaderTag::new(HeaderTagFlag::Optional))
+            );
+            assert!(header.get_tag::<AddressHeaderTag>().is_none());
+            assert!(header.get_tag::<InformationRequestHeaderTag>().is_none());
+            assert_eq!(header.get_tags::<EndHeaderTag>().count(), 1);
+            assert_eq!(header.get_tags::<ConsoleHeaderTag>().count(), 0);
+        }
+
+        #[test]
+        fn test_verify_checksum() {
+            let structure = Builder::new(HeaderTagISA::MIPS32)
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let header = load(&structure);
+            assert!(header.verify_checksum());
+
+            let mut base = *structure.header();
+            assert!(base.verify_checksum());
+            base.checksum = base.checksum.wrapping_add(1);
+            assert!(!base.verify_checksum());
+            let mut base = *structure.header();
+            base.length += 8;
+            assert!(!base.verify_checksum());
+        }
+
+        #[test]
+        fn test_linker_snippet() {
+            let structure = Builder::new(HeaderTagISA::I386).build();
+            let snippet = load(&structure).linker_snippet(".multiboot2_header");
+            assert!(snippet.contains(".multiboot2_header ALIGN(8) :"));
+            assert!(snippet.contains("KEEP(*(.multiboot2_header))"));
+            assert!(snippet.contains("8-byte aligned"));
+            assert!(snippet.contains("24 bytes"));
+        }
+
+        #[test]
+        fn test_to_builder_source() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Optional))
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let src = load(&structure).to_builder_source().unwrap();
+            assert!(src.starts_with("Builder::new(HeaderTagISA::I386)"));
+            assert!(src
+                .contains(".module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Optional))"));
+            assert!(src.contains(
+                ".relocatable_tag(RelocatableHeaderTag::new(HeaderTagFlag::Required, 0x1000, 0x10000, 0x1000, RelocatableHeaderTagPreference::None))"
+            ));
+            assert!(src.ends_with(".build()"));
+
+            let structure = Builder::new(HeaderTagISA::MIPS32)
+                .information_request_tag(InformationRequestHeaderTag::new(
+                    HeaderTagFlag::Optional,
+                    &[MbiTagType::Cmdline.into(), MbiTagType::Custom(42).into()],
+                ))
+                .build();
+            let src = load(&structure).to_builder_source().unwrap();
+            assert_eq!(
+                src,
+                "Builder::new(HeaderTagISA::MIPS32)\n    \
+                 .information_request_tag(InformationRequestHeaderTag::new(\n        \
+                 HeaderTagFlag::Optional,\n        &[\n            \
+                 MbiTagType::Cmdline.into(),\n            \
+                 MbiTagType::Custom(42).into(),\n        ],\n    ))\n    \
+                 .build()"
+            );
+
+            // Unknown architecture 7 with an updated checksum.
+            let mut bytes = structure.as_bytes().to_vec();
+            let length = load(&structure).length();
+            let checksum = Multiboot2BasicHeader::calc_checksum_raw(MAGIC, 7, length);
+            bytes[4..8].copy_from_slice(&7_u32.to_ne_bytes());
+            bytes[12..16].copy_from_slice(&checksum.to_ne_bytes());
+            let owned = OwnedMultiboot2Header::from_vec(bytes).unwrap();
+            assert_eq!(
+                owned.header().to_builder_source(),
+                Err(BuilderSourceError::UnknownArch(UnknownHeaderTagISA(7)))
+            );
+        }
+
+        #[test]
+        fn test_needs_elf_entry() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            assert!(load(&structure).needs_elf_entry());
+
+            let structure = Builder::new(HeaderTagISA::I386)
+                .entry_tag(EntryAddressHeaderTag::new(HeaderTagFlag::Required, 0x2000))
+                .build();
+            assert!(!load(&structure).needs_elf_entry());
+
+            let structure = Builder::new(HeaderTagISA::I386)
+                .efi_64_tag(EntryEfi64HeaderTag::new(HeaderTagFlag::Required, 0x2000))
+                .build();
+            assert!(!load(&structure).needs_elf_entry());
+        }
+
+        #[test]
+        fn test_arch() {
+            for arch in [HeaderTagISA::I386, HeaderTagISA::MIPS32] {
+                let structure = Builder::new(arch).build();
+                let header = load(&structure);
+                assert_eq!(header.arch(), Ok(arch));
+                assert_eq!(header.arch_raw(), arch as u32);
+                assert_eq!(header.architecture(), Ok(arch));
+            }
+        }
+
+        #[test]
+        fn test_unknown_arch() {
+            let structure = Builder::new(HeaderTagISA::I386).build();
+            let mut bytes = structure.as_bytes().to_vec();
+            let length = load(&structure).length();
+            let checksum = 0_u32
+                .wrapping_sub(MAGIC)
+                .wrapping_sub(7)
+                .wrapping_sub(length);
+            bytes[4..8].copy_from_slice(&7_u32.to_le_bytes());
+            bytes[12..16].copy_from_slice(&checksum.to_le_bytes());
+
+            let (_, owned) = Multiboot2Header::find_in_realigned(&bytes).unwrap();
+            let header = owned.header();
+            assert!(header.verify_checksum());
+            assert_eq!(header.arch_raw(), 7);
+            assert_eq!(header.arch(), Err(UnknownHeaderTagISA(7)));
+            assert_eq!(header.architecture(), Err(7));
+        }
+
+        #[test]
+        fn test_loader_plan() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .entry_tag(EntryAddressHeaderTag::new(HeaderTagFlag::Required, 0x5000))
+                .console_tag(ConsoleHeaderTag::new(
+                    HeaderTagFlag::Optional,
+                    ConsoleHeaderTagFlags::EgaTextSupported,
+                ))
+                .framebuffer_tag(FramebufferHeaderTag::new(
+                    HeaderTagFlag::Optional,
+                    1024,
+                    768,
+                    32,
+                ))
+                .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required))
+                .efi_bs_tag(EfiBootServiceHeaderTag::new(HeaderTagFlag::Optional))
+                .efi_32_tag(EntryEfi32HeaderTag::new(HeaderTagFlag::Required, 0x7000))
+                .efi_64_tag(EntryEfi64HeaderTag::new(HeaderTagFlag::Required, 0x8000))
+                .relocatable_tag(RelocatableHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    0x1000,
+                    0x1000_0000,
+                    4096,
+                    RelocatableHeaderTagPreference::High,
+                ))
+                .build();
+            let header = load(&structure);
+
+            let ctx = BootContext {
+                firmware_64bit: true,
+                efi: true,
+                free_memory: &[(0x10_0000, 0x10_0000)],
+                image_size: 0x2000,
+            };
+            assert_eq!(
+                header.loader_plan(ctx),
+                LoaderPlan {
+                    load_base: Some(0x1f_e000),
+                    entry: EntryPoint::Efi64(0x8000),
+                    display: DisplayPreference::Framebuffer {
+                        width: 1024,
+                        height: 768,
+                        depth: 32,
+                    },
+                    page_align_modules: true,
+                    keep_boot_services: true,
+                }
+            );
+
+            // On legacy BIOS, the EFI entry points are irrelevant.
+            let ctx = BootContext { efi: false, ..ctx };
+            let plan = header.loader_plan(ctx);
+            assert_eq!(plan.entry, EntryPoint::Address(0x5000));
+            assert!(!plan.keep_boot_services);
+        }
+
+        #[test]
+        #[cfg(feature = "std")]
+        fn test_from_reader() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let mut bytes = structure.as_bytes().to_vec();
+            bytes.extend_from_slice(b"rest of the file");
+
+            let mut cursor = std::io::Cursor::new(&bytes);
+            let owned = Multiboot2Header::from_reader(&mut cursor).unwrap();
+            assert_eq!(cursor.position(), u64::from(owned.header().length()));
+            assert_eq!(
+                owned.header().relocatable_tag(),
+                Some(&relocatable_tag(0x1000, 0x10000))
+            );
+
+            let read_invalid = |bytes: &[u8]| {
+                let err = Multiboot2Header::from_reader(&mut std::io::Cursor::new(bytes));
+                assert_eq!(err.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+            };
+            let with_length = |length: u32| {
+                let mut bytes = bytes.clone();
+                let checksum = Multiboot2BasicHeader::calc_checksum_raw(MAGIC, 0, length);
+                bytes[8..12].copy_from_slice(&length.to_ne_bytes());
+                bytes[12..16].copy_from_slice(&checksum.to_ne_bytes());
+                bytes
+            };
+
+            // Implausibly large or unaligned lengths are rejected before the
+            // remaining bytes are read.
+            read_invalid(&with_length(u32::MAX & !7));
+            read_invalid(&with_length(owned.header().length() + 4));
+
+            // The tags are validated: The relocatable tag becomes a malformed
+            // information request.
+            let mut malformed = bytes.clone();
+            malformed[16..18].copy_from_slice(&1_u16.to_ne_bytes());
+            malformed[20..24].copy_from_slice(&22_u32.to_ne_bytes());
+            read_invalid(&malformed);
+
+            // Tags of a bad size don't panic but are invalid data.
+            let relocatable = HeaderTagType::Relocatable as u16;
+            for (typ, size) in [(0x99, 0), (0x99, 64), (relocatable, 16)] {
+                read_invalid(&super::header_with_tag(typ, size).0);
+            }
+
+            bytes[0] = 0;
+            read_invalid(&bytes);
+        }
+
+        #[test]
+        fn test_fits_within() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let header = load(&structure);
+            let length = header.length() as usize;
+            assert!(header.fits_within(length));
+            assert!(header.fits_within(length + 1));
+            assert!(!header.fits_within(length - 1));
+        }
+
+        #[test]
+        fn test_efi_tags() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .efi_bs_tag(EfiBootServiceHeaderTag::new(HeaderTagFlag::Required))
+                .efi_64_tag(EntryEfi64HeaderTag::new(HeaderTagFlag::Required, 0x8000))
+                .build();
+            assert_eq!(
+                load(&structure).efi_tags(),
+                EfiTagSummary {
+                    boot_services: true,
+                    efi32_entry: false,
+                    efi64_entry: true,
+                    efi_info_requests: false,
+                }
+            );
+        }
+
+        #[test]
+        fn test_display_tags_consistent() {
+            let ega_text = ConsoleHeaderTag::new(
+                HeaderTagFlag::Required,
+                ConsoleHeaderTagFlags::EgaTextSupported,
+            );
+            let framebuffer = FramebufferHeaderTag::new(HeaderTagFlag::Required, 1024, 768, 32);
+
+            let structure = Builder::new(HeaderTagISA::I386)
+                .console_tag(ega_text)
+                .build();
+            assert!(load(&structure).display_tags_consistent());
+
+            let structure = Builder::new(HeaderTagISA::I386)
+                .framebuffer_tag(framebuffer)
+                .build();
+            assert!(load(&structure).display_tags_consistent());
+
+            let structure = Builder::new(HeaderTagISA::I386)
+                .console_tag(ega_text)
+                .framebuffer_tag(FramebufferHeaderTag::new(
+                    HeaderTagFlag::Optional,
+                    1024,
+                    768,
+                    32,
+                ))
+                .build();
+            assert!(load(&structure).display_tags_consistent());
+
+            let structure = Builder::new(HeaderTagISA::I386)
+                .console_tag(ega_text)
+                .framebuffer_tag(framebuffer)
+                .build();
+            assert!(!load(&structure).display_tags_consistent());
+        }
+
+        #[test]
+        fn test_owned_from_vec() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let bytes = structure.as_bytes().to_vec();
+
+            let mut trailing = bytes.clone();
+            trailing.extend_from_slice(&[0xff; 8]);
+            let ptr = trailing.as_ptr();
+            let owned = OwnedMultiboot2Header::from_vec(trailing).unwrap();
+            assert_eq!(owned.header().length() as usize, bytes.len());
+            assert_eq!(owned.header().as_bytes(), &bytes[..]);
+            assert_eq!(
+                owned.header().relocatable_tag(),
+                Some(&relocatable_tag(0x1000, 0x10000))
+            );
+            if ptr.align_offset(8) == 0 {
+                // The allocation is reused.
+                assert_eq!(owned.header().as_bytes().as_ptr(), ptr);
+            }
+
+            // The allocation of a Vec<u8> is usually 8-byte aligned, so the
+            // realignment is tested with a deliberately misaligned buffer.
+            let mut misaligned = AlignedBytes([0_u8; 64]);
+            misaligned.0[4..4 + bytes.len()].copy_from_slice(&bytes);
+            let owned = OwnedMultiboot2Header::copy_from(&misaligned.0[4..]).unwrap();
+            assert_eq!(owned.header().as_bytes(), &bytes[..]);
+            assert_eq!(
+                owned.header().relocatable_tag(),
+                Some(&relocatable_tag(0x1000, 0x10000))
+            );
+            // Turn the relocatable tag into a malformed information request.
+            misaligned.0[4 + 16..4 + 18].copy_from_slice(&1_u16.to_ne_bytes());
+            misaligned.0[4 + 20..4 + 24].copy_from_slice(&22_u32.to_ne_bytes());
+            assert_eq!(
+                OwnedMultiboot2Header::copy_from(&misaligned.0[4..]).unwrap_err(),
+                LoadError::MalformedInfoRequest { size: 22 }
+            );
+
+            let mut corrupted = bytes.clone();
+            corrupted[12] ^= 1;
+            assert_eq!(
+                OwnedMultiboot2Header::from_vec(corrupted).unwrap_err(),
+                LoadError::ChecksumMismatch
+            );
+            assert_eq!(
+                OwnedMultiboot2Header::from_vec(bytes[..bytes.len() - 8].to_vec()).unwrap_err(),
+                LoadError::Memory(MemoryError::InvalidReportedTotalSize)
+            );
+
+            // Tags of a bad size are rejected before anything is copied.
+            let err = LoadError::Memory(MemoryError::InvalidReportedTotalSize);
+            let relocatable = HeaderTagType::Relocatable as u16;
+            for (typ, size) in [(0x99, 0), (0x99, 64), (relocatable, 16)] {
+                let bytes = super::header_with_tag(typ, size);
+                assert_eq!(
+                    OwnedMultiboot2Header::from_vec(bytes.0.to_vec()).unwrap_err(),
+                    err
+                );
+                let mut misaligned = AlignedBytes([0_u8; 48]);
+                misaligned.0[4..44].copy_from_slice(&bytes.0);
+                assert_eq!(
+                    OwnedMultiboot2Header::copy_from(&misaligned.0[4..]).unwrap_err(),
+                    err
+                );
+            }
+        }
+
+        #[test]
+        fn test_repack() {
+            // Both tags require padding.
+            let structure = Builder::new(HeaderTagISA::I386)
+                .information_request_tag(InformationRequestHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    &[MbiTagType::Cmdline.into()],
+                ))
+                .framebuffer_tag(FramebufferHeaderTag::new(
+                    HeaderTagFlag::Optional,
+                    1024,
+                    768,
+                    32,
+                ))
+                .build();
+            let bytes = structure.as_bytes().to_vec();
+            let repacked = load(&structure).repack();
+            assert_eq!(repacked.len(), bytes.len());
+
+            // Append a module align tag after the end tag.
+            let mut padded = bytes.clone();
+            padded.extend_from_slice(&[6, 0, 0, 0, 8, 0, 0, 0]);
+            let length = padded.len() as u32;
+            let checksum = Multiboot2BasicHeader::calc_checksum_raw(MAGIC, 0, length);
+            padded[8..12].copy_from_slice(&length.to_le_bytes());
+            padded[12..16].copy_from_slice(&checksum.to_le_bytes());
+            let owned = OwnedMultiboot2Header::from_vec(padded.clone()).unwrap();
+            assert_eq!(owned.header().length_discrepancy(), 8);
+
+            let repacked = owned.header().repack();
+            assert_eq!(repacked.len(), bytes.len());
+            let owned = OwnedMultiboot2Header::from_vec(repacked).unwrap();
+            let header = owned.header();
+            assert!(header.module_align_tag().is_none());
+            assert!(header.framebuffer_tag().is_some());
+            assert_eq!(header.iter().count(), 3);
+
+            // The padded information request tag moves behind the module align
+            // tag, which needs no padding.
+            let structure = Builder::new(HeaderTagISA::I386)
+                .information_request_tag(InformationRequestHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    &[MbiTagType::Cmdline.into()],
+                ))
+                .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required))
+                .build();
+            let repacked = load(&structure).repack();
+            assert!(repacked.len() <= structure.as_bytes().len());
+            let owned = OwnedMultiboot2Header::from_vec(repacked).unwrap();
+            let header = owned.header();
+            assert!(header.verify_checksum());
+            let types = header
+                .iter()
+                .map(|tag| tag.header().typ())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                types,
+                [
+                    Ok(HeaderTagType::ModuleAlign),
+                    Ok(HeaderTagType::InformationRequest),
+                    Ok(HeaderTagType::End)
+                ]
+            );
+        }
+
+        #[test]
+        fn test_requests_acpi() {
+            let structure = Builder::new(HeaderTagISA::I386).build();
+            assert_eq!(load(&structure).requests_acpi(), (false, false));
+
+            let structure = Builder::new(HeaderTagISA::I386)
+                .information_request_tag(InformationRequestHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    &[MbiTagType::AcpiV1.into(), MbiTagType::AcpiV2.into()],
+                ))
+                .build();
+            assert_eq!(load(&structure).requests_acpi(), (true, true));
+        }
+
+        #[test]
+        fn test_requests_in_second_information_request_tag() {
+            let second_requests = [
+                MbiTagType::AcpiV2,
+                MbiTagType::LoadBaseAddr,
+                MbiTagType::EfiMmap,
+            ]
+            .into_iter()
+            .flat_map(|typ| u32::from(typ).to_ne_bytes())
+            .collect::<Vec<_>>();
+            let structure = Builder::new(HeaderTagISA::I386)
+                .information_request_tag(InformationRequestHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    &[MbiTagType::Mmap.into()],
+                ))
+                .vendor_tag(
+                    HeaderTagType::InformationRequest as u16,
+                    HeaderTagFlag::Optional,
+                    &second_requests,
+                )
+                .build();
+            let header = load(&structure);
+            assert_eq!(header.requests_acpi(), (false, true));
+            assert!(header.requests_load_base());
+            assert!(header.efi_tags().efi_info_requests);
+        }
+
+        #[test]
+        fn test_requests_load_base() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            assert!(!load(&structure).requests_load_base());
+
+            let structure = Builder::new(HeaderTagISA::I386)
+                .information_request_tag(InformationRequestHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    &[MbiTagType::LoadBaseAddr.into()],
+                ))
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let header = load(&structure);
+            assert!(header.requests_load_base());
+            let requests = header
+                .information_request_tag()
+                .unwrap()
+                .requests()
+                .iter()
+                .map(|&request| MbiTagType::from(request))
+                .collect::<Vec<_>>();
+            assert_eq!(requests, [MbiTagType::LoadBaseAddr]);
+        }
+
+        #[test]
+        fn test_collect_tags() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required))
+                .efi_bs_tag(EfiBootServiceHeaderTag::new(HeaderTagFlag::Required))
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let header = load(&structure);
+
+            let (tags, count) = header.collect_tags::<5>().unwrap();
+            assert_eq!(count, 4);
+            let types = tags[..count]
+                .iter()
+                .map(|tag| tag.unwrap().typ().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                types,
+                [
+                    HeaderTagType::ModuleAlign,
+                    HeaderTagType::EfiBS,
+                    HeaderTagType::Relocatable,
+                    HeaderTagType::End
+                ]
+            );
+            assert_eq!(tags[4], None);
+
+            assert_eq!(
+                header.collect_tags::<2>().unwrap_err(),
+                CollectError { capacity: 2 }
+            );
+        }
+
+        #[test]
+        fn test_base() {
+            let structure = Builder::new(HeaderTagISA::MIPS32)
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let header = load(&structure);
+            assert_eq!(
+                header.base(),
+                BaseHeader {
+                    magic: MAGIC,
+                    arch: HeaderTagISA::MIPS32 as u32,
+                    header_length: structure.as_bytes().len() as u32,
+                    checksum: Multiboot2BasicHeader::calc_checksum(
+                        MAGIC,
+                        HeaderTagISA::MIPS32,
+                        structure.as_bytes().len() as u32
+                    ),
+                }
+            );
+        }
+
+        #[test]
+        fn test_mbi_tag_order() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .information_request_tag(InformationRequestHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    &[
+                        MbiTagType::Mmap.into(),
+                        MbiTagType::Cmdline.into(),
+                        MbiTagType::AcpiV2.into(),
+                        MbiTagType::Mmap.into(),
+                    ],
+                ))
+                .build();
+            assert_eq!(
+                load(&structure).mbi_tag_order(),
+                [MbiTagType::Mmap, MbiTagType::Cmdline, MbiTagType::AcpiV2]
+            );
+
+            // Requests of all information request tags are considered.
+            let second_requests = [MbiTagType::AcpiV2, MbiTagType::LoadBaseAddr]
+                .into_iter()
+                .flat_map(|typ| u32::from(typ).to_ne_bytes())
+                .collect::<Vec<_>>();
+            let structure = Builder::new(HeaderTagISA::I386)
+                .information_request_tag(InformationRequestHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    &[MbiTagType::Mmap.into()],
+                ))
+                .vendor_tag(
+                    HeaderTagType::InformationRequest as u16,
+                    HeaderTagFlag::Optional,
+                    &second_requests,
+                )
+                .build();
+            assert_eq!(
+                load(&structure).mbi_tag_order(),
+                [
+                    MbiTagType::Mmap,
+                    MbiTagType::AcpiV2,
+                    MbiTagType::LoadBaseAddr
+                ]
+            );
+
+            let structure = Builder::new(HeaderTagISA::I386).build();
+            assert!(load(&structure).mbi_tag_order().is_empty());
+        }
+
+        #[test]
+        fn test_is_32bit_bootable() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .entry_tag(EntryAddressHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    0x10_0000,
+                ))
+                .relocatable_tag(relocatable_tag(0x10_0000, 0x1000_0000))
+                .build();
+            assert!(load(&structure).is_32bit_bootable());
+
+            let structure = Builder::new(HeaderTagISA::I386)
+                .efi_bs_tag(EfiBootServiceHeaderTag::new(HeaderTagFlag::Required))
+                .efi_64_tag(EntryEfi64HeaderTag::new(HeaderTagFlag::Required, 0x20_0000))
+                .build();
+            assert!(!load(&structure).is_32bit_bootable());
+
+            let structure = Builder::new(HeaderTagISA::MIPS32).build();
+            assert!(!load(&structure).is_32bit_bootable());
+        }
+
+        #[test]
+        fn test_tag_byte_ranges() {
+            let structure = Builder::new(HeaderTagISA::I386)
+                .console_tag(ConsoleHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    ConsoleHeaderTagFlags::ConsoleRequired,
+                ))
+                .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Optional))
+                .relocatable_tag(relocatable_tag(0x1000, 0x10000))
+                .build();
+            let header = load(&structure);
+            let ranges = header.tag_byte_ranges().collect::<Vec<_>>();
+            assert_eq!(ranges, [16..28, 32..40, 40..64, 64..72]);
+
+            // The ranges are contiguous, except for the padding, and cover the
+            // whole header after the basic header.
+            let mut end = size_of::<Multiboot2BasicHeader>();
+            for range in ranges {
+                assert_eq!(range.start, increase_to_alignment(end));
+                end = range.end;
+            }
+            assert_eq!(increase_to_alignment(end), header.length() as usize);
+        }
+
+        #[test]
+        fn test_boot_equivalent() {
+            let ctx = BootContext {
+                firmware_64bit: true,
+                efi: false,
+                free_memory: &[(0x10_0000, 0x100_0000)],
+                image_size: 0x1000,
+            };
+            let structure_a = Builder::new(HeaderTagISA::I386)
+                .entry_tag(EntryAddressHeaderTag::new(HeaderTagFlag::Required, 0x5000))
+                .build();
+            // Differs in the bytes, but the EFI tags are irrelevant for a
+            // legacy BIOS boot.
+            let structure_b = Builder::new(HeaderTagISA::I386)
+                .entry_tag(EntryAddressHeaderTag::new(HeaderTagFlag::Optional, 0x5000))
+                .efi_64_tag(EntryEfi64HeaderTag::new(HeaderTagFlag::Optional, 0x8000))
+                .build();
+            assert_ne!(structure_a.as_bytes().len(), structure_b.as_bytes().len());
+            let (header_a, header_b) = (load(&structure_a), load(&structure_b));
+            assert!(header_a.boot_equivalent(&header_b, ctx));
+
+            let structure_c = Builder::new(HeaderTagISA::I386)
+                .entry_tag(EntryAddressHeaderTag::new(HeaderTagFlag::Required, 0x6000))
+                .build();
+            assert!(!header_a.boot_equivalent(&load(&structure_c), ctx));
+        }
+    }
 }
@@ -1,5 +1,5 @@
 use crate::{HeaderTagFlag, HeaderTagHeader};
-use crate::{HeaderTagType, MbiTagTypeId};
+use crate::{HeaderTagType, MbiTagType, MbiTagTypeId};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use core::mem;
@@ -9,6 +9,7 @@ use multiboot2_common::{MaybeDynSized, Tag};
 #[cfg(feature = "builder")]
 use {
     alloc::boxed::Box,
+    alloc::vec::Vec,
     core::{ptr, slice},
 };
 
@@ -34,10 +35,25 @@ impl InformationRequestHeaderTag {
         new_boxed(header, &[requests])
     }
 
+    /// Like [`Self::new`], but takes the requests from an iterator. This
+    /// avoids temporary arrays, for example, when the requests are filtered.
+    #[cfg(feature = "builder")]
+    #[must_use]
+    pub fn from_requests(
+        flags: HeaderTagFlag,
+        requests: impl IntoIterator<Item = MbiTagType>,
+    ) -> Box<Self> {
+        let requests = requests
+            .into_iter()
+            .map(MbiTagTypeId::from)
+            .collect::<Vec<_>>();
+        Self::new(flags, &requests)
+    }
+
     /// Returns the [`HeaderTagType`].
     #[must_use]
     pub const fn typ(&self) -> HeaderTagType {
-        self.header.typ()
+        Self::ID
     }
 
     /// Returns the [`HeaderTagFlag`]s.
@@ -57,6 +73,42 @@ impl InformationRequestHeaderTag {
     pub const fn requests(&self) -> &[MbiTagTypeId] {
         &self.requests
     }
+
+    /// Returns the raw bytes of the requests, i.e., the `size() - 8` bytes
+    /// after the tag header. In contrast to [`MaybeDynSized::payload`], the
+    /// padding after the tag is not included.
+    #[must_use]
+    pub fn payload(&self) -> &[u8] {
+        let ptr = core::ptr::addr_of!(self.requests).cast::<u8>();
+        // SAFETY: The requests are plain integers without padding.
+        unsafe { core::slice::from_raw_parts(ptr, mem::size_of_val(&self.requests)) }
+    }
+
+    /// Returns the requested MBI tag types in order. Types unknown to this
+    /// crate are reported as [`MbiTagType::Custom`]. The number of requests
+    /// is derived from the size of the tag.
+    pub fn requested_types(&self) -> impl Iterator<Item = MbiTagType> + '_ {
+        self.requests()
+            .iter()
+            .map(|&request| MbiTagType::from(request))
+    }
+
+    /// Checks whether the MBI tag type `typ` is requested.
+    #[must_use]
+    pub fn contains(&self, typ: MbiTagType) -> bool {
+        self.requested_types().any(|request| request == typ)
+    }
+
+    /// Checks whether every MBI tag type requested by this tag is also
+    /// requested by `other`. The order, duplicates, and the flags of the tags
+    /// are not taken into account. For example, this checks whether a loader
+    /// that describes its capabilities as a header satisfies a kernel.
+    #[must_use]
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        self.requests()
+            .iter()
+            .all(|request| other.requests().contains(request))
+    }
 }
 
 impl Debug for InformationRequestHeaderTag {
@@ -76,7 +128,9 @@ impl MaybeDynSized for InformationRequestHeaderTag {
     const BASE_SIZE: usize = mem::size_of::<HeaderTagHeader>();
 
     fn dst_len(header: &Self::Header) -> Self::Metadata {
-        let dst_size = header.size() as usize - Self::BASE_SIZE;
+        // A size below the header size is rejected when loading the header.
+        // Guard against it anyway, as there are no requests in that case.
+        let dst_size = (header.size() as usize).saturating_sub(Self::BASE_SIZE);
         assert_eq!(dst_size % mem::size_of::<MbiTagTypeId>(), 0);
         dst_size / mem::size_of::<MbiTagTypeId>()
     }
@@ -91,7 +145,6 @@ impl Tag for InformationRequestHeaderTag {
 #[cfg(feature = "builder")]
 mod tests {
     use super::*;
-    use crate::MbiTagType;
 
     #[test]
     fn creation() {
@@ -124,4 +177,106 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn payload() {
+        let tag = InformationRequestHeaderTag::new(
+            HeaderTagFlag::Optional,
+            &[
+                MbiTagType::Cmdline.into(),
+                MbiTagType::Mmap.into(),
+                MbiTagType::Framebuffer.into(),
+            ],
+        );
+        let payload = tag.payload();
+        assert_eq!(payload.len(), tag.size() as usize - 8);
+        assert_eq!(payload, [1, 0, 0, 0, 6, 0, 0, 0, 8, 0, 0, 0]);
+    }
+
+    #[test]
+    fn from_requests() {
+        let efi_available = false;
+        let tag = InformationRequestHeaderTag::from_requests(
+            HeaderTagFlag::Required,
+            [
+                MbiTagType::Cmdline,
+                MbiTagType::EfiMmap,
+                MbiTagType::Mmap,
+                MbiTagType::EfiBs,
+            ]
+            .into_iter()
+            .filter(|&typ| {
+                efi_available || !matches!(typ, MbiTagType::EfiMmap | MbiTagType::EfiBs)
+            }),
+        );
+        let expected: [MbiTagTypeId; 2] = [MbiTagType::Cmdline.into(), MbiTagType::Mmap.into()];
+        assert_eq!(tag.flags(), HeaderTagFlag::Required);
+        assert_eq!(tag.requests(), expected);
+    }
+
+    #[test]
+    fn is_subset_of() {
+        let tag = |requests: &[MbiTagType]| {
+            InformationRequestHeaderTag::from_requests(
+                HeaderTagFlag::Required,
+                requests.iter().copied(),
+            )
+        };
+        let small = tag(&[MbiTagType::Mmap, MbiTagType::Cmdline]);
+        let big = tag(&[
+            MbiTagType::Cmdline,
+            MbiTagType::Framebuffer,
+            MbiTagType::Mmap,
+        ]);
+
+        // subset
+        assert!(small.is_subset_of(&big));
+        // equal
+        assert!(small.is_subset_of(&small));
+        assert!(small.is_subset_of(&tag(&[MbiTagType::Cmdline, MbiTagType::Mmap])));
+        // superset
+        assert!(!big.is_subset_of(&small));
+        // the empty set is a subset of everything
+        assert!(tag(&[]).is_subset_of(&small));
+        assert!(!small.is_subset_of(&tag(&[])));
+    }
+
+    #[test]
+    fn contains() {
+        let tag = InformationRequestHeaderTag::new(
+            HeaderTagFlag::Required,
+            &[
+                MbiTagType::Cmdline.into(),
+                MbiTagType::Custom(0x1337).into(),
+            ],
+        );
+        assert!(tag.contains(MbiTagType::Cmdline));
+        assert!(tag.contains(MbiTagType::Custom(0x1337)));
+        assert!(!tag.contains(MbiTagType::Mmap));
+        assert!(
+            !InformationRequestHeaderTag::new(HeaderTagFlag::Required, &[])
+                .contains(MbiTagType::Cmdline)
+        );
+    }
+
+    #[test]
+    fn requested_types() {
+        let tag = InformationRequestHeaderTag::new(
+            HeaderTagFlag::Required,
+            &[
+                MbiTagType::Cmdline.into(),
+                MbiTagType::Mmap.into(),
+                MbiTagTypeId::from(0x1337),
+            ],
+        );
+        assert_eq!(tag.size(), 8 + 3 * 4);
+        assert_eq!(
+            tag.requested_types().collect::<Vec<_>>(),
+            [
+                MbiTagType::Cmdline,
+                MbiTagType::Mmap,
+                MbiTagType::Custom(0x1337)
+            ]
+        );
+    }
 }
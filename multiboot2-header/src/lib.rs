@@ -44,11 +44,11 @@
 #![deny(rustdoc::all)]
 // --- END STYLE CHECKS ---
 
-#[cfg(feature = "builder")]
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
 #[cfg_attr(test, macro_use)]
-#[cfg(test)]
+#[cfg(any(test, feature = "std"))]
 extern crate std;
 
 /// Iterator over the tags of a Multiboot2 boot information.
@@ -67,10 +67,13 @@ mod entry_efi_64;
 mod framebuffer;
 mod header;
 mod information_request;
+mod loader_plan;
 mod module_align;
+mod parsed_tag;
 mod relocatable;
 mod tags;
 mod uefi_bs;
+mod visitor;
 
 #[cfg(feature = "builder")]
 mod builder;
@@ -86,12 +89,17 @@ pub use self::entry_efi_64::*;
 pub use self::framebuffer::*;
 pub use self::header::*;
 pub use self::information_request::*;
+pub use self::loader_plan::*;
 pub use self::module_align::*;
+pub use self::parsed_tag::*;
 pub use self::relocatable::*;
 pub use self::tags::*;
 pub use self::uefi_bs::*;
+pub use self::visitor::*;
 #[cfg(feature = "builder")]
-pub use builder::Builder;
+pub use builder::{
+    padding_between, BuildIntoError, Builder, BuilderError, FramebufferRequestWarning, Preset,
+};
 
 /// Re-export of [`multiboot2::TagType`] from `multiboot2`-crate.
 pub use multiboot2::{TagType as MbiTagType, TagTypeId as MbiTagTypeId};
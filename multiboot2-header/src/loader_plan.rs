@@ -0,0 +1,67 @@
+//! Module for [`LoaderPlan`] and [`BootContext`].
+//!
+//! See [`crate::Multiboot2Header::loader_plan`].
+
+/// Properties of the boot environment of a loader, which are relevant to
+/// create a [`LoaderPlan`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BootContext<'a> {
+    /// Whether the firmware runs in 64-bit mode.
+    pub firmware_64bit: bool,
+    /// Whether the machine was booted via UEFI (in contrast to legacy BIOS).
+    pub efi: bool,
+    /// Free memory regions as `(start, length)` pairs.
+    pub free_memory: &'a [(u64, u64)],
+    /// The size of the image in memory, including the bss segment. Required
+    /// to place relocatable images.
+    pub image_size: u64,
+}
+
+/// The entry point that a loader uses to hand off control to the payload.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EntryPoint {
+    /// The entry point of the ELF header of the payload.
+    Elf,
+    /// The address of the [`crate::EntryAddressHeaderTag`].
+    Address(u32),
+    /// The address of the [`crate::EntryEfi32HeaderTag`].
+    Efi32(u32),
+    /// The address of the [`crate::EntryEfi64HeaderTag`].
+    Efi64(u32),
+}
+
+/// The display mode that the payload prefers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DisplayPreference {
+    /// The payload has no preference.
+    None,
+    /// The payload supports EGA text mode. See [`crate::ConsoleHeaderTag`].
+    EgaText,
+    /// The payload prefers a graphical framebuffer. See
+    /// [`crate::FramebufferHeaderTag`].
+    Framebuffer {
+        /// Preferred width. `0` means no preference.
+        width: u32,
+        /// Preferred height. `0` means no preference.
+        height: u32,
+        /// Preferred depth. `0` means no preference.
+        depth: u32,
+    },
+}
+
+/// All decisions that a loader needs to take on the basis of a Multiboot2
+/// header and its [`BootContext`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LoaderPlan {
+    /// The base address for a relocatable image, if the header contains a
+    /// [`crate::RelocatableHeaderTag`] and the image fits in the free memory.
+    pub load_base: Option<u64>,
+    /// The entry point to jump to.
+    pub entry: EntryPoint,
+    /// The preferred display mode.
+    pub display: DisplayPreference,
+    /// Whether boot modules must be page-aligned.
+    pub page_align_modules: bool,
+    /// Whether the loader must not terminate the UEFI boot services.
+    pub keep_boot_services: bool,
+}
@@ -10,6 +10,10 @@ pub struct ModuleAlignHeaderTag {
 }
 
 impl ModuleAlignHeaderTag {
+    /// The alignment of boot modules that the tag requests, i.e., the page
+    /// size.
+    pub const ALIGNMENT: u64 = 4096;
+
     /// Constructs a new tag.
     #[must_use]
     pub const fn new(flags: HeaderTagFlag) -> Self {
@@ -24,7 +28,7 @@ impl ModuleAlignHeaderTag {
     /// Returns the [`HeaderTagType`].
     #[must_use]
     pub const fn typ(&self) -> HeaderTagType {
-        self.header.typ()
+        Self::ID
     }
 
     /// Returns the [`HeaderTagFlag`]s.
@@ -38,6 +42,13 @@ impl ModuleAlignHeaderTag {
     pub const fn size(&self) -> u32 {
         self.header.size()
     }
+
+    /// Returns whether a loader that places boot modules at a multiple of
+    /// `available_align` satisfies the requirement of this tag.
+    #[must_use]
+    pub const fn can_satisfy_alignment(available_align: u64) -> bool {
+        available_align != 0 && available_align % Self::ALIGNMENT == 0
+    }
 }
 
 impl MaybeDynSized for ModuleAlignHeaderTag {
@@ -55,10 +66,28 @@ impl Tag for ModuleAlignHeaderTag {
 
 #[cfg(test)]
 mod tests {
-    use crate::ModuleAlignHeaderTag;
+    use crate::{HeaderTagFlag, HeaderTagType, ModuleAlignHeaderTag};
 
     #[test]
     fn test_assert_size() {
         assert_eq!(core::mem::size_of::<ModuleAlignHeaderTag>(), 2 + 2 + 4);
     }
+
+    #[test]
+    fn test_new() {
+        for flags in [HeaderTagFlag::Optional, HeaderTagFlag::Required] {
+            let tag = ModuleAlignHeaderTag::new(flags);
+            assert_eq!(tag.typ(), HeaderTagType::ModuleAlign);
+            assert_eq!(tag.flags(), flags);
+            assert_eq!(tag.size(), 8);
+        }
+    }
+
+    #[test]
+    fn test_can_satisfy_alignment() {
+        assert!(ModuleAlignHeaderTag::can_satisfy_alignment(4096));
+        assert!(ModuleAlignHeaderTag::can_satisfy_alignment(0x20_0000));
+        assert!(!ModuleAlignHeaderTag::can_satisfy_alignment(2048));
+        assert!(!ModuleAlignHeaderTag::can_satisfy_alignment(0));
+    }
 }
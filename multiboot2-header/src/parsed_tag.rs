@@ -0,0 +1,133 @@
+//! Module for [`ParsedHeaderTag`].
+
+use crate::{
+    AddressHeaderTag, ConsoleHeaderTag, EfiBootServiceHeaderTag, EndHeaderTag,
+    EntryAddressHeaderTag, EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag,
+    HeaderTag, HeaderTagFlag, HeaderTagHeader, HeaderTagType, InformationRequestHeaderTag,
+    ModuleAlignHeaderTag, RelocatableHeaderTag, UnknownHeaderTagType,
+};
+use multiboot2_common::{DynSizedStructure, MaybeDynSized};
+
+/// A header tag casted to its concrete type on the basis of its
+/// [`HeaderTagType`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParsedHeaderTag<'a> {
+    /// See [`EndHeaderTag`].
+    End(&'a EndHeaderTag),
+    /// See [`InformationRequestHeaderTag`].
+    InformationRequest(&'a InformationRequestHeaderTag),
+    /// See [`AddressHeaderTag`].
+    Address(&'a AddressHeaderTag),
+    /// See [`EntryAddressHeaderTag`].
+    EntryAddress(&'a EntryAddressHeaderTag),
+    /// See [`ConsoleHeaderTag`].
+    ConsoleFlags(&'a ConsoleHeaderTag),
+    /// See [`FramebufferHeaderTag`].
+    Framebuffer(&'a FramebufferHeaderTag),
+    /// See [`ModuleAlignHeaderTag`].
+    ModuleAlign(&'a ModuleAlignHeaderTag),
+    /// See [`EfiBootServiceHeaderTag`].
+    EfiBS(&'a EfiBootServiceHeaderTag),
+    /// See [`EntryEfi32HeaderTag`].
+    EntryAddressEFI32(&'a EntryEfi32HeaderTag),
+    /// See [`EntryEfi64HeaderTag`].
+    EntryAddressEFI64(&'a EntryEfi64HeaderTag),
+    /// See [`RelocatableHeaderTag`].
+    Relocatable(&'a RelocatableHeaderTag),
+    /// A tag whose type is unknown to this crate.
+    Unknown(&'a DynSizedStructure<HeaderTagHeader>),
+}
+
+impl<'a> ParsedHeaderTag<'a> {
+    /// Casts a generic tag, as emitted by [`crate::TagIter`], to its concrete
+    /// type. Tags of an unknown type are kept as [`Self::Unknown`].
+    #[must_use]
+    pub fn from_generic(tag: &'a DynSizedStructure<HeaderTagHeader>) -> Self {
+        let Ok(typ) = tag.header().typ() else {
+            return Self::Unknown(tag);
+        };
+        match typ {
+            // Some tools emit end tags that are bigger than 8 bytes. They are
+            // tolerated, so only the common prefix is viewed as end tag.
+            HeaderTagType::End => {
+                let ptr = core::ptr::addr_of!(*tag).cast::<EndHeaderTag>();
+                // SAFETY: The tag covers at least the header, which is
+                // everything an end tag consists of. The alignment is the same.
+                Self::End(unsafe { &*ptr })
+            }
+            HeaderTagType::InformationRequest => Self::InformationRequest(tag.cast()),
+            HeaderTagType::Address => Self::Address(tag.cast()),
+            HeaderTagType::EntryAddress => Self::EntryAddress(tag.cast()),
+            HeaderTagType::ConsoleFlags => Self::ConsoleFlags(tag.cast()),
+            HeaderTagType::Framebuffer => Self::Framebuffer(tag.cast()),
+            HeaderTagType::ModuleAlign => Self::ModuleAlign(tag.cast()),
+            HeaderTagType::EfiBS => Self::EfiBS(tag.cast()),
+            HeaderTagType::EntryAddressEFI32 => Self::EntryAddressEFI32(tag.cast()),
+            HeaderTagType::EntryAddressEFI64 => Self::EntryAddressEFI64(tag.cast()),
+            HeaderTagType::Relocatable => Self::Relocatable(tag.cast()),
+        }
+    }
+
+    /// Returns the common [`HeaderTagHeader`] of the tag.
+    #[must_use]
+    pub fn header(&self) -> &'a HeaderTagHeader {
+        match *self {
+            Self::End(tag) => tag.header(),
+            Self::InformationRequest(tag) => tag.header(),
+            Self::Address(tag) => tag.header(),
+            Self::EntryAddress(tag) => tag.header(),
+            Self::ConsoleFlags(tag) => tag.header(),
+            Self::Framebuffer(tag) => tag.header(),
+            Self::ModuleAlign(tag) => tag.header(),
+            Self::EfiBS(tag) => tag.header(),
+            Self::EntryAddressEFI32(tag) => tag.header(),
+            Self::EntryAddressEFI64(tag) => tag.header(),
+            Self::Relocatable(tag) => tag.header(),
+            Self::Unknown(tag) => tag.header(),
+        }
+    }
+
+    /// Returns the tag as [`HeaderTag`] trait object. Returns `None` for
+    /// dynamically sized tags, i.e., [`Self::InformationRequest`] and
+    /// [`Self::Unknown`], as references to them can't be turned into trait
+    /// objects.
+    #[must_use]
+    pub fn as_dyn(&self) -> Option<&'a dyn HeaderTag> {
+        match *self {
+            Self::End(tag) => Some(tag),
+            Self::Address(tag) => Some(tag),
+            Self::EntryAddress(tag) => Some(tag),
+            Self::ConsoleFlags(tag) => Some(tag),
+            Self::Framebuffer(tag) => Some(tag),
+            Self::ModuleAlign(tag) => Some(tag),
+            Self::EfiBS(tag) => Some(tag),
+            Self::EntryAddressEFI32(tag) => Some(tag),
+            Self::EntryAddressEFI64(tag) => Some(tag),
+            Self::Relocatable(tag) => Some(tag),
+            Self::InformationRequest(_) | Self::Unknown(_) => None,
+        }
+    }
+
+    /// Returns the [`HeaderTagType`]. Fails for [`Self::Unknown`].
+    pub fn typ(&self) -> Result<HeaderTagType, UnknownHeaderTagType> {
+        self.header().typ()
+    }
+
+    /// Returns the raw type of the tag.
+    #[must_use]
+    pub fn typ_raw(&self) -> u16 {
+        self.header().typ_raw()
+    }
+
+    /// Returns the [`HeaderTagFlag`]s.
+    #[must_use]
+    pub fn flags(&self) -> HeaderTagFlag {
+        self.header().flags()
+    }
+
+    /// Returns the size.
+    #[must_use]
+    pub fn size(&self) -> u32 {
+        self.header().size()
+    }
+}
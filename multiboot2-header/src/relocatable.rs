@@ -1,7 +1,8 @@
-use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
+use crate::{AddressOverflowError, HeaderTagFlag, HeaderTagHeader, HeaderTagType};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use core::mem;
+use core::num::NonZeroU64;
 use multiboot2_common::{MaybeDynSized, Tag};
 
 /// It contains load address placement suggestion for boot loader. Boot loader
@@ -19,6 +20,41 @@ pub enum RelocatableHeaderTagPreference {
     High = 2,
 }
 
+impl RelocatableHeaderTagPreference {
+    /// Converts the raw value to a [`RelocatableHeaderTagPreference`]. This
+    /// is `const` in contrast to the [`TryFrom`] implementation.
+    pub const fn from_u32(value: u32) -> Result<Self, UnknownRelocatableHeaderTagPreference> {
+        match value {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Low),
+            2 => Ok(Self::High),
+            _ => Err(UnknownRelocatableHeaderTagPreference(value)),
+        }
+    }
+}
+
+impl TryFrom<u32> for RelocatableHeaderTagPreference {
+    type Error = UnknownRelocatableHeaderTagPreference;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Self::from_u32(value)
+    }
+}
+
+impl From<RelocatableHeaderTagPreference> for u32 {
+    fn from(value: RelocatableHeaderTagPreference) -> Self {
+        value as Self
+    }
+}
+
+/// Error when an unknown [`RelocatableHeaderTagPreference`] is found.
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[display(fmt = "Unknown relocatable header tag preference {}", _0)]
+pub struct UnknownRelocatableHeaderTagPreference(pub u32);
+
+#[cfg(feature = "unstable")]
+impl core::error::Error for UnknownRelocatableHeaderTagPreference {}
+
 /// This tag indicates that the image is relocatable.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C, align(8))]
@@ -30,11 +66,16 @@ pub struct RelocatableHeaderTag {
     max_addr: u32,
     /// Image alignment in memory, e.g. 4096.
     align: u32,
-    preference: RelocatableHeaderTagPreference,
+    /// The raw [`RelocatableHeaderTagPreference`], which might be unknown.
+    preference: u32,
 }
 
 impl RelocatableHeaderTag {
     /// Constructs a new tag.
+    ///
+    /// In debug builds, this panics on values that loaders refuse, namely a
+    /// minimum address bigger than the maximum address and an alignment that
+    /// is zero or not a power of two. Use [`Self::try_new`] to handle them.
     #[must_use]
     pub const fn new(
         flags: HeaderTagFlag,
@@ -42,6 +83,20 @@ impl RelocatableHeaderTag {
         max_addr: u32,
         align: u32,
         preference: RelocatableHeaderTagPreference,
+    ) -> Self {
+        debug_assert!(min_addr <= max_addr, "min_addr must not exceed max_addr");
+        debug_assert!(align.is_power_of_two(), "align must be a power of two");
+        Self::new_unchecked(flags, min_addr, max_addr, align, preference)
+    }
+
+    /// Like [`Self::new`] but without any checks, e.g., to test how invalid
+    /// tags are handled.
+    pub(crate) const fn new_unchecked(
+        flags: HeaderTagFlag,
+        min_addr: u32,
+        max_addr: u32,
+        align: u32,
+        preference: RelocatableHeaderTagPreference,
     ) -> Self {
         let header = HeaderTagHeader::new(
             HeaderTagType::Relocatable,
@@ -53,14 +108,99 @@ impl RelocatableHeaderTag {
             min_addr,
             max_addr,
             align,
-            preference,
+            preference: preference as u32,
+        }
+    }
+
+    /// Like [`Self::new`] but rejects values that loaders refuse, namely a
+    /// minimum address bigger than the maximum address and an alignment that
+    /// is zero or not a power of two.
+    pub const fn try_new(
+        flags: HeaderTagFlag,
+        min_addr: u32,
+        max_addr: u32,
+        align: u32,
+        preference: RelocatableHeaderTagPreference,
+    ) -> Result<Self, RelocatableHeaderTagError> {
+        if min_addr > max_addr {
+            return Err(RelocatableHeaderTagError::InvalidRange { min_addr, max_addr });
+        }
+        if align == 0 {
+            return Err(RelocatableHeaderTagError::ZeroAlign);
         }
+        if !align.is_power_of_two() {
+            return Err(RelocatableHeaderTagError::NonPowerOfTwoAlign(align));
+        }
+        Ok(Self::new(flags, min_addr, max_addr, align, preference))
+    }
+
+    /// Like [`Self::new`] but takes the addresses as `u64`, as it is common
+    /// on 64-bit hosts.
+    ///
+    /// Fails if any address doesn't fit into the 32-bit fields of the tag.
+    pub fn new_u64(
+        flags: HeaderTagFlag,
+        min_addr: u64,
+        max_addr: u64,
+        align: u32,
+        preference: RelocatableHeaderTagPreference,
+    ) -> Result<Self, AddressOverflowError> {
+        Ok(Self::new(
+            flags,
+            AddressOverflowError::check(min_addr)?,
+            AddressOverflowError::check(max_addr)?,
+            align,
+            preference,
+        ))
+    }
+
+    /// Like [`Self::new`] but takes the alignment as [`NonZeroU64`], which
+    /// rules out an alignment of zero by construction.
+    ///
+    /// Fails if the alignment doesn't fit into the 32-bit field of the tag.
+    pub fn with_align_nonzero(
+        flags: HeaderTagFlag,
+        min_addr: u32,
+        max_addr: u32,
+        align: NonZeroU64,
+        preference: RelocatableHeaderTagPreference,
+    ) -> Result<Self, AddressOverflowError> {
+        Ok(Self::new(
+            flags,
+            min_addr,
+            max_addr,
+            AddressOverflowError::check(align.get())?,
+            preference,
+        ))
+    }
+
+    /// Constructs a new tag from a range of pages, as paging code usually
+    /// thinks in pages. `min_addr` is the start of `min_page` and `max_addr`
+    /// is the start of `max_page`, i.e., the image must end before
+    /// `max_page`. The image is aligned to `page_size`.
+    ///
+    /// Fails if any resulting address or the page size doesn't fit into the
+    /// 32-bit fields of the tag.
+    pub fn from_page_range(
+        flags: HeaderTagFlag,
+        min_page: u64,
+        max_page: u64,
+        page_size: u64,
+        preference: RelocatableHeaderTagPreference,
+    ) -> Result<Self, AddressOverflowError> {
+        Ok(Self::new(
+            flags,
+            AddressOverflowError::check(min_page.saturating_mul(page_size))?,
+            AddressOverflowError::check(max_page.saturating_mul(page_size))?,
+            AddressOverflowError::check(page_size)?,
+            preference,
+        ))
     }
 
     /// Returns the [`HeaderTagType`].
     #[must_use]
     pub const fn typ(&self) -> HeaderTagType {
-        self.header.typ()
+        Self::ID
     }
 
     /// Returns the [`HeaderTagFlag`]s.
@@ -93,11 +233,113 @@ impl RelocatableHeaderTag {
         self.align
     }
 
-    /// Return the preference.
+    /// Returns the [`RelocatableHeaderTagPreference`] or an error, if the
+    /// preference is unknown. See [`Self::preference_raw`].
+    pub const fn preference(
+        &self,
+    ) -> Result<RelocatableHeaderTagPreference, UnknownRelocatableHeaderTagPreference> {
+        RelocatableHeaderTagPreference::from_u32(self.preference)
+    }
+
+    /// Returns the raw value of the preference, which might not be a known
+    /// [`RelocatableHeaderTagPreference`].
     #[must_use]
-    pub const fn preference(&self) -> RelocatableHeaderTagPreference {
+    pub const fn preference_raw(&self) -> u32 {
         self.preference
     }
+
+    /// Checks whether any address in `[min_addr, max_addr]` satisfies the
+    /// alignment of the tag. If not, for example, because the alignment is
+    /// bigger than the address range, no loader can place the image. An
+    /// alignment of `0` is treated as no alignment.
+    #[must_use]
+    pub const fn is_satisfiable(&self) -> bool {
+        let align = if self.align == 0 {
+            1
+        } else {
+            self.align as u64
+        };
+        let min_addr = self.min_addr as u64;
+        let lowest_aligned = (min_addr + align - 1) / align * align;
+        lowest_aligned <= self.max_addr as u64
+    }
+
+    /// Chooses the base address at which a loader places an image of
+    /// `image_size` bytes, given the free memory `regions` as
+    /// `(start, length)` pairs. The chosen base respects the min address,
+    /// the max address, and the alignment of the tag. Across all regions,
+    /// the base is picked according to the [`RelocatableHeaderTagPreference`].
+    /// [`RelocatableHeaderTagPreference::None`] and unknown preferences are
+    /// treated like [`RelocatableHeaderTagPreference::Low`].
+    ///
+    /// Returns `None` if no region can hold the image.
+    #[must_use]
+    pub fn choose_base(
+        &self,
+        image_size: u64,
+        regions: impl IntoIterator<Item = (u64, u64)>,
+    ) -> Option<u64> {
+        let align = u64::from(self.align.max(1));
+        let high = self.preference() == Ok(RelocatableHeaderTagPreference::High);
+        let candidates = regions.into_iter().filter_map(|(start, len)| {
+            let lowest_base = start.max(u64::from(self.min_addr));
+            let end = start.checked_add(len)?.min(u64::from(self.max_addr));
+            let highest_base = end.checked_sub(image_size)?;
+            let base = if high {
+                highest_base / align * align
+            } else {
+                lowest_base.checked_add(align - 1)? / align * align
+            };
+            (lowest_base..=highest_base).contains(&base).then_some(base)
+        });
+        if high {
+            candidates.max()
+        } else {
+            candidates.min()
+        }
+    }
+
+    /// Returns the number of aligned base addresses that lie within both the
+    /// region `[region_start, region_end)` and `[min_addr, max_addr]`.
+    /// Loaders that randomize the load address (ASLR) can pick a random
+    /// slot index below this count. An alignment of `0` is treated as no
+    /// alignment.
+    ///
+    /// The size of the image is not taken into account.
+    #[must_use]
+    pub fn slot_count(&self, region_start: u64, region_end: u64) -> u64 {
+        self.slot_bounds(region_start, region_end)
+            .map_or(0, |(lowest_base, highest_base)| {
+                (highest_base - lowest_base) / u64::from(self.align.max(1)) + 1
+            })
+    }
+
+    /// Returns the `index`-th aligned base address that lies within both the
+    /// region `[region_start, region_end)` and `[min_addr, max_addr]`, in
+    /// ascending order. Loaders implementing ASLR combine this with a random
+    /// index below [`Self::slot_count`].
+    ///
+    /// Returns `None` if `index` is out of range.
+    #[must_use]
+    pub fn nth_slot(&self, region_start: u64, region_end: u64, index: u64) -> Option<u64> {
+        let (lowest_base, highest_base) = self.slot_bounds(region_start, region_end)?;
+        let base = index
+            .checked_mul(u64::from(self.align.max(1)))
+            .and_then(|offset| lowest_base.checked_add(offset))?;
+        (base <= highest_base).then_some(base)
+    }
+
+    /// Returns the lowest and the highest aligned base address within both
+    /// the region and `[min_addr, max_addr]`, if any.
+    fn slot_bounds(&self, region_start: u64, region_end: u64) -> Option<(u64, u64)> {
+        let align = u64::from(self.align.max(1));
+        let last = region_end.checked_sub(1)?;
+        let lowest = region_start.max(u64::from(self.min_addr));
+        let highest = last.min(u64::from(self.max_addr));
+        let lowest_base = lowest.checked_add(align - 1)? / align * align;
+        let highest_base = highest / align * align;
+        (lowest_base <= highest_base).then_some((lowest_base, highest_base))
+    }
 }
 
 impl Debug for RelocatableHeaderTag {
@@ -110,11 +352,50 @@ impl Debug for RelocatableHeaderTag {
             .field("min_addr", &(self.min_addr as *const u32))
             .field("max_addr", &(self.max_addr as *const u32))
             .field("align", &{ self.align })
-            .field("preference", &{ self.preference })
+            .field("preference", &self.preference())
             .finish()
     }
 }
 
+/// Errors of [`RelocatableHeaderTag::try_new`].
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RelocatableHeaderTagError {
+    /// The minimum address is bigger than the maximum address.
+    #[display(fmt = "Invalid relocatable range {:#x}..={:#x}", min_addr, max_addr)]
+    InvalidRange {
+        /// The minimum address.
+        min_addr: u32,
+        /// The maximum address.
+        max_addr: u32,
+    },
+    /// The alignment is zero.
+    #[display(fmt = "The alignment is zero")]
+    ZeroAlign,
+    /// The alignment is not a power of two.
+    #[display(fmt = "The alignment {:#x} is not a power of two", _0)]
+    NonPowerOfTwoAlign(u32),
+}
+
+#[cfg(feature = "unstable")]
+impl core::error::Error for RelocatableHeaderTagError {}
+
+/// Prints the tag compactly, e.g.,
+/// `relocatable: [0x1337..0xdeadbeef] align=4096 pref=None`. Unknown
+/// preferences are printed as raw value.
+impl fmt::Display for RelocatableHeaderTag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "relocatable: [{:#x}..{:#x}] align={} pref=",
+            self.min_addr, self.max_addr, self.align
+        )?;
+        match self.preference() {
+            Ok(preference) => write!(f, "{preference:?}"),
+            Err(_) => write!(f, "{}", self.preference),
+        }
+    }
+}
+
 impl MaybeDynSized for RelocatableHeaderTag {
     type Header = HeaderTagHeader;
 
@@ -130,7 +411,12 @@ impl Tag for RelocatableHeaderTag {
 
 #[cfg(test)]
 mod tests {
-    use crate::RelocatableHeaderTag;
+    use crate::{
+        AddressOverflowError, HeaderTagFlag, RelocatableHeaderTag, RelocatableHeaderTagError,
+        RelocatableHeaderTagPreference, UnknownRelocatableHeaderTagPreference,
+    };
+    use core::num::NonZeroU64;
+    use std::string::ToString;
 
     #[test]
     fn test_assert_size() {
@@ -139,4 +425,318 @@ mod tests {
             2 + 2 + 4 + 4 + 4 + 4 + 4
         );
     }
+
+    #[test]
+    fn test_try_new() {
+        let tag = |min_addr, max_addr, align| {
+            RelocatableHeaderTag::try_new(
+                HeaderTagFlag::Required,
+                min_addr,
+                max_addr,
+                align,
+                RelocatableHeaderTagPreference::None,
+            )
+        };
+        let valid = tag(0x1000, 0x10000, 4096).unwrap();
+        assert_eq!(valid.min_addr(), 0x1000);
+        assert_eq!(valid.max_addr(), 0x10000);
+        assert_eq!(valid.align(), 4096);
+        assert!(tag(0x1000, 0x1000, 1).is_ok());
+
+        assert_eq!(
+            tag(0x10000, 0x1000, 4096),
+            Err(RelocatableHeaderTagError::InvalidRange {
+                min_addr: 0x10000,
+                max_addr: 0x1000
+            })
+        );
+        assert_eq!(
+            tag(0x1000, 0x10000, 0),
+            Err(RelocatableHeaderTagError::ZeroAlign)
+        );
+        assert_eq!(
+            tag(0x1000, 0x10000, 0x3000),
+            Err(RelocatableHeaderTagError::NonPowerOfTwoAlign(0x3000))
+        );
+    }
+
+    #[test]
+    fn test_new_u64() {
+        let tag = RelocatableHeaderTag::new_u64(
+            HeaderTagFlag::Required,
+            0x1000,
+            u64::from(u32::MAX),
+            4096,
+            RelocatableHeaderTagPreference::None,
+        )
+        .unwrap();
+        assert_eq!(tag.min_addr(), 0x1000);
+        assert_eq!(tag.max_addr(), u32::MAX);
+
+        let err = RelocatableHeaderTag::new_u64(
+            HeaderTagFlag::Required,
+            0x1000,
+            0x1_0000_0000,
+            4096,
+            RelocatableHeaderTagPreference::None,
+        );
+        assert_eq!(err, Err(AddressOverflowError(0x1_0000_0000)));
+    }
+
+    #[test]
+    fn test_with_align_nonzero() {
+        let tag = RelocatableHeaderTag::with_align_nonzero(
+            HeaderTagFlag::Required,
+            0x1000,
+            0x10000,
+            NonZeroU64::new(4096).unwrap(),
+            RelocatableHeaderTagPreference::None,
+        )
+        .unwrap();
+        assert_eq!(tag.align(), 4096);
+
+        let err = RelocatableHeaderTag::with_align_nonzero(
+            HeaderTagFlag::Required,
+            0x1000,
+            0x10000,
+            NonZeroU64::new(0x1_0000_0000).unwrap(),
+            RelocatableHeaderTagPreference::None,
+        );
+        assert_eq!(err, Err(AddressOverflowError(0x1_0000_0000)));
+    }
+
+    #[test]
+    fn test_from_page_range() {
+        let tag = RelocatableHeaderTag::from_page_range(
+            HeaderTagFlag::Required,
+            0x100,
+            0x400,
+            4096,
+            RelocatableHeaderTagPreference::Low,
+        )
+        .unwrap();
+        assert_eq!(tag.min_addr(), 0x10_0000);
+        assert_eq!(tag.max_addr(), 0x40_0000);
+        assert_eq!(tag.align(), 4096);
+        assert_eq!(tag.preference(), Ok(RelocatableHeaderTagPreference::Low));
+
+        let tag = RelocatableHeaderTag::from_page_range(
+            HeaderTagFlag::Required,
+            1,
+            2,
+            0x20_0000,
+            RelocatableHeaderTagPreference::None,
+        )
+        .unwrap();
+        assert_eq!(tag.min_addr(), 0x20_0000);
+        assert_eq!(tag.max_addr(), 0x40_0000);
+        assert_eq!(tag.align(), 0x20_0000);
+
+        let err = RelocatableHeaderTag::from_page_range(
+            HeaderTagFlag::Required,
+            0x100,
+            0x10_0000,
+            4096,
+            RelocatableHeaderTagPreference::None,
+        );
+        assert_eq!(err, Err(AddressOverflowError(0x1_0000_0000)));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "min_addr must not exceed max_addr"]
+    fn test_new_invalid_range() {
+        let _ = RelocatableHeaderTag::new(
+            HeaderTagFlag::Required,
+            0x2000,
+            0x1000,
+            4096,
+            RelocatableHeaderTagPreference::None,
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "align must be a power of two"]
+    fn test_new_invalid_align() {
+        let _ = RelocatableHeaderTag::new(
+            HeaderTagFlag::Required,
+            0x1000,
+            0x2000,
+            4095,
+            RelocatableHeaderTagPreference::None,
+        );
+    }
+
+    #[test]
+    fn test_is_satisfiable() {
+        let tag = |min_addr, max_addr, align| {
+            RelocatableHeaderTag::new_unchecked(
+                HeaderTagFlag::Required,
+                min_addr,
+                max_addr,
+                align,
+                RelocatableHeaderTagPreference::None,
+            )
+        };
+        assert!(tag(0x1000, 0x2000, 0x1000).is_satisfiable());
+        assert!(tag(0x1000, 0x2000, 0).is_satisfiable());
+        assert!(tag(0x10000, 0x11000, 0x10000).is_satisfiable());
+        assert!(!tag(0x1000, 0x2000, 0x10000).is_satisfiable());
+        assert!(!tag(0x2000, 0x1000, 0x1000).is_satisfiable());
+        assert!(!tag(u32::MAX - 1, u32::MAX, 0x8000_0000).is_satisfiable());
+    }
+
+    #[test]
+    fn test_choose_base() {
+        let regions = [
+            (0x0, 0x10_0000),
+            (0x20_0000, 0x10_0000),
+            // too small for the image
+            (0x80_0000, 0x1000),
+        ];
+        let tag = |preference| {
+            RelocatableHeaderTag::new(
+                HeaderTagFlag::Required,
+                0x1000,
+                0x100_0000,
+                0x1000,
+                preference,
+            )
+        };
+
+        assert_eq!(
+            tag(RelocatableHeaderTagPreference::High).choose_base(0x2000, regions),
+            Some(0x2f_e000)
+        );
+        assert_eq!(
+            tag(RelocatableHeaderTagPreference::Low).choose_base(0x2000, regions),
+            Some(0x1000)
+        );
+        assert_eq!(
+            tag(RelocatableHeaderTagPreference::None).choose_base(0x2000, regions),
+            Some(0x1000)
+        );
+        assert_eq!(
+            tag(RelocatableHeaderTagPreference::High).choose_base(0x20_0000, regions),
+            None
+        );
+    }
+
+    #[test]
+    fn test_slot_count() {
+        let tag = RelocatableHeaderTag::new(
+            HeaderTagFlag::Required,
+            0x10_0000,
+            0x20_0000,
+            0x1000,
+            RelocatableHeaderTagPreference::None,
+        );
+        // Region within [min_addr, max_addr].
+        assert_eq!(tag.slot_count(0x10_0000, 0x10_4000), 4);
+        // Unaligned region start.
+        assert_eq!(tag.slot_count(0x10_0001, 0x10_4000), 3);
+        // Region exceeding [min_addr, max_addr] on both sides.
+        assert_eq!(tag.slot_count(0, 0x1_0000_0000), 0x101);
+        // Region outside of [min_addr, max_addr].
+        assert_eq!(tag.slot_count(0, 0x10_0000), 0);
+        assert_eq!(tag.slot_count(0x30_0000, 0x40_0000), 0);
+        // Empty region.
+        assert_eq!(tag.slot_count(0x10_0000, 0x10_0000), 0);
+        assert_eq!(tag.slot_count(0, 0), 0);
+    }
+
+    #[test]
+    fn test_nth_slot() {
+        let tag = RelocatableHeaderTag::new(
+            HeaderTagFlag::Required,
+            0x10_0000,
+            0x20_0000,
+            0x1000,
+            RelocatableHeaderTagPreference::None,
+        );
+        let count = tag.slot_count(0x10_0001, 0x10_4000);
+        assert_eq!(count, 3);
+        assert_eq!(tag.nth_slot(0x10_0001, 0x10_4000, 0), Some(0x10_1000));
+        assert_eq!(
+            tag.nth_slot(0x10_0001, 0x10_4000, count - 1),
+            Some(0x10_3000)
+        );
+        assert_eq!(tag.nth_slot(0x10_0001, 0x10_4000, count), None);
+        assert_eq!(tag.nth_slot(0x10_0001, 0x10_4000, u64::MAX), None);
+        assert_eq!(tag.nth_slot(0, 0x10_0000, 0), None);
+    }
+
+    #[test]
+    fn test_display() {
+        let tag = RelocatableHeaderTag::new(
+            HeaderTagFlag::Required,
+            0x1337,
+            0xdeadbeef,
+            4096,
+            RelocatableHeaderTagPreference::None,
+        );
+        assert_eq!(
+            tag.to_string(),
+            "relocatable: [0x1337..0xdeadbeef] align=4096 pref=None"
+        );
+        assert_eq!(
+            (
+                tag.min_addr(),
+                tag.max_addr(),
+                tag.align(),
+                tag.preference()
+            ),
+            (
+                0x1337,
+                0xdeadbeef,
+                4096,
+                Ok(RelocatableHeaderTagPreference::None)
+            )
+        );
+    }
+
+    #[test]
+    fn test_unknown_preference() {
+        let mut tag = RelocatableHeaderTag::new(
+            HeaderTagFlag::Required,
+            0x1000,
+            0x10000,
+            4096,
+            RelocatableHeaderTagPreference::High,
+        );
+        assert_eq!(tag.preference_raw(), 2);
+        // Loaded headers may contain any value.
+        tag.preference = 3;
+        assert_eq!(
+            tag.preference(),
+            Err(UnknownRelocatableHeaderTagPreference(3))
+        );
+        assert_eq!(tag.preference_raw(), 3);
+        assert_eq!(
+            tag.to_string(),
+            "relocatable: [0x1000..0x10000] align=4096 pref=3"
+        );
+        assert_eq!(tag.choose_base(0x1000, [(0, 0x10_0000)]), Some(0x1000));
+    }
+
+    #[test]
+    fn test_preference_conversion() {
+        for preference in [
+            RelocatableHeaderTagPreference::None,
+            RelocatableHeaderTagPreference::Low,
+            RelocatableHeaderTagPreference::High,
+        ] {
+            let raw = u32::from(preference);
+            assert_eq!(
+                RelocatableHeaderTagPreference::try_from(raw),
+                Ok(preference)
+            );
+        }
+        assert_eq!(u32::from(RelocatableHeaderTagPreference::High), 2);
+        assert_eq!(
+            RelocatableHeaderTagPreference::try_from(3),
+            Err(UnknownRelocatableHeaderTagPreference(3))
+        );
+    }
 }
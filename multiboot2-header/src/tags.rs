@@ -2,8 +2,11 @@
 //! code at the end of the official Multiboot2 spec. These tags follow in memory right after
 //! [`crate::Multiboot2BasicHeader`].
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt::Debug;
 use core::mem;
-use multiboot2_common::Header;
+use multiboot2_common::{Header, MaybeDynSized};
 
 /// ISA/ARCH in Multiboot2 header.
 #[repr(u32)]
@@ -18,33 +21,76 @@ pub enum HeaderTagISA {
     MIPS32 = 4,
 }
 
+impl HeaderTagISA {
+    /// Converts the raw value to a [`HeaderTagISA`]. This is `const` in
+    /// contrast to the [`TryFrom`] implementation.
+    pub const fn from_u32(value: u32) -> Result<Self, UnknownHeaderTagISA> {
+        match value {
+            0 => Ok(Self::I386),
+            4 => Ok(Self::MIPS32),
+            _ => Err(UnknownHeaderTagISA(value)),
+        }
+    }
+}
+
+impl TryFrom<u32> for HeaderTagISA {
+    type Error = UnknownHeaderTagISA;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Self::from_u32(value)
+    }
+}
+
+/// Error when an unknown [`HeaderTagISA`] is found.
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[display(fmt = "Unknown architecture {}", _0)]
+pub struct UnknownHeaderTagISA(pub u32);
+
+#[cfg(feature = "unstable")]
+impl core::error::Error for UnknownHeaderTagISA {}
+
 /// Possible types for header tags of a Multiboot2 header. The names and values are taken
 /// from the example C code at the bottom of the Multiboot2 specification. This value
 /// stands in the `typ` property of [`HeaderTagHeader`].
+///
+/// The [`Display`](core::fmt::Display) implementation prints human-readable
+/// names, such as `information-request`. Unknown types are reported as
+/// [`UnknownHeaderTagType`], which prints the raw value in hex.
 #[repr(u16)]
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum HeaderTagType {
     /// Type for [`crate::EndHeaderTag`].
+    #[display(fmt = "end")]
     End = 0,
     /// Type for [`crate::InformationRequestHeaderTag`].
+    #[display(fmt = "information-request")]
     InformationRequest = 1,
     /// Type for [`crate::AddressHeaderTag`].
+    #[display(fmt = "address")]
     Address = 2,
     /// Type for [`crate::EntryAddressHeaderTag`].
+    #[display(fmt = "entry-address")]
     EntryAddress = 3,
     /// Type for [`crate::ConsoleHeaderTag`].
+    #[display(fmt = "console-flags")]
     ConsoleFlags = 4,
     /// Type for [`crate::FramebufferHeaderTag`].
+    #[display(fmt = "framebuffer")]
     Framebuffer = 5,
     /// Type for [`crate::ModuleAlignHeaderTag`].
+    #[display(fmt = "module-align")]
     ModuleAlign = 6,
     /// Type for [`crate::EfiBootServiceHeaderTag`].
+    #[display(fmt = "efi-bs")]
     EfiBS = 7,
     /// Type for [`crate::EntryEfi32HeaderTag`].
+    #[display(fmt = "entry-address-efi32")]
     EntryAddressEFI32 = 8,
     /// Type for [`crate::EntryEfi64HeaderTag`].
+    #[display(fmt = "entry-address-efi64")]
     EntryAddressEFI64 = 9,
     /// Type for [`crate::RelocatableHeaderTag`].
+    #[display(fmt = "relocatable")]
     Relocatable = 10,
 }
 
@@ -54,8 +100,43 @@ impl HeaderTagType {
     pub const fn count() -> u32 {
         11
     }
+
+    /// Converts the raw value to a [`HeaderTagType`]. This is `const` in
+    /// contrast to the [`TryFrom`] implementation.
+    pub const fn from_u16(value: u16) -> Result<Self, UnknownHeaderTagType> {
+        match value {
+            0 => Ok(Self::End),
+            1 => Ok(Self::InformationRequest),
+            2 => Ok(Self::Address),
+            3 => Ok(Self::EntryAddress),
+            4 => Ok(Self::ConsoleFlags),
+            5 => Ok(Self::Framebuffer),
+            6 => Ok(Self::ModuleAlign),
+            7 => Ok(Self::EfiBS),
+            8 => Ok(Self::EntryAddressEFI32),
+            9 => Ok(Self::EntryAddressEFI64),
+            10 => Ok(Self::Relocatable),
+            _ => Err(UnknownHeaderTagType(value)),
+        }
+    }
 }
 
+impl TryFrom<u16> for HeaderTagType {
+    type Error = UnknownHeaderTagType;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        Self::from_u16(value)
+    }
+}
+
+/// Error when an unknown [`HeaderTagType`] is found.
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[display(fmt = "Unknown header tag type {:#x}", _0)]
+pub struct UnknownHeaderTagType(pub u16);
+
+#[cfg(feature = "unstable")]
+impl core::error::Error for UnknownHeaderTagType {}
+
 /// Flags for Multiboot2 header tags.
 #[repr(u16)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -67,14 +148,40 @@ pub enum HeaderTagFlag {
     Optional = 1,
 }
 
+impl HeaderTagFlag {
+    /// Converts the raw flags of a tag to a [`HeaderTagFlag`]. Only bit 0 is
+    /// considered, as all other bits are reserved.
+    #[must_use]
+    pub const fn from_bits(bits: u16) -> Self {
+        if bits & Self::Optional as u16 == 0 {
+            Self::Required
+        } else {
+            Self::Optional
+        }
+    }
+
+    /// Returns whether the tag is [`HeaderTagFlag::Required`].
+    #[must_use]
+    pub const fn is_required(&self) -> bool {
+        matches!(self, Self::Required)
+    }
+
+    /// Returns whether the tag is [`HeaderTagFlag::Optional`].
+    #[must_use]
+    pub const fn is_optional(&self) -> bool {
+        matches!(self, Self::Optional)
+    }
+}
+
 /// The common header that all header tags share. Specific tags may have
 /// additional fields that depend on the `typ` and the `size` field.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C)]
 pub struct HeaderTagHeader {
-    typ: HeaderTagType, /* u16 */
-    // u16 value
-    flags: HeaderTagFlag, /* u16 */
+    // u16 value of a HeaderTagType, which may be unknown to this crate
+    typ: u16,
+    // u16 value of the flags, where only bit 0 is defined by the spec
+    flags: u16,
     size: u32,
     // Followed by optional additional tag specific fields.
 }
@@ -83,21 +190,52 @@ impl HeaderTagHeader {
     /// Creates a new header.
     #[must_use]
     pub const fn new(typ: HeaderTagType, flags: HeaderTagFlag, size: u32) -> Self {
-        Self { typ, flags, size }
+        Self::new_raw(typ as u16, flags, size)
     }
 
-    /// Returns the [`HeaderTagType`].
+    /// Creates a new header with a raw type, which may be unknown to this
+    /// crate.
     #[must_use]
-    pub const fn typ(&self) -> HeaderTagType {
+    pub const fn new_raw(typ: u16, flags: HeaderTagFlag, size: u32) -> Self {
+        Self {
+            typ,
+            flags: flags as u16,
+            size,
+        }
+    }
+
+    /// Returns the [`HeaderTagType`]. Fails if the type is unknown to this
+    /// crate. The raw value is available via [`Self::typ_raw`].
+    pub const fn typ(&self) -> Result<HeaderTagType, UnknownHeaderTagType> {
+        HeaderTagType::from_u16(self.typ)
+    }
+
+    /// Returns the raw type of the tag.
+    #[must_use]
+    pub const fn typ_raw(&self) -> u16 {
         self.typ
     }
 
-    /// Returns the [`HeaderTagFlag`]s.
+    /// Returns the [`HeaderTagFlag`]s. Only bit 0 of the raw value is
+    /// considered, as all other bits are reserved.
     #[must_use]
     pub const fn flags(&self) -> HeaderTagFlag {
+        HeaderTagFlag::from_bits(self.flags)
+    }
+
+    /// Returns the raw flags of the tag, including reserved bits.
+    #[must_use]
+    pub const fn flags_raw(&self) -> u16 {
         self.flags
     }
 
+    /// Returns whether any reserved bit of the flags is set, i.e., any bit
+    /// besides bit 0.
+    #[must_use]
+    pub const fn has_reserved_flag_bits(&self) -> bool {
+        self.flags & !(HeaderTagFlag::Optional as u16) != 0
+    }
+
     /// Returns the size.
     #[must_use]
     pub const fn size(&self) -> u32 {
@@ -105,6 +243,45 @@ impl HeaderTagHeader {
     }
 }
 
+/// Error that is returned if an address doesn't fit into the 32-bit address
+/// field of a header tag.
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[display(fmt = "Address {:#x} exceeds the 32-bit address field", _0)]
+pub struct AddressOverflowError(pub u64);
+
+#[cfg(feature = "unstable")]
+impl core::error::Error for AddressOverflowError {}
+
+impl AddressOverflowError {
+    /// Converts a 64-bit address to the 32-bit value that is stored in the
+    /// address fields of header tags. Fails instead of silently truncating.
+    pub(crate) const fn check(addr: u64) -> Result<u32, Self> {
+        if addr > u32::MAX as u64 {
+            Err(Self(addr))
+        } else {
+            Ok(addr as u32)
+        }
+    }
+}
+
+/// Returns the bytes of a single header tag as they appear in a Multiboot2
+/// header, including the zeroed padding to the next 8-byte boundary.
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn tag_to_bytes<T: HeaderTag + MaybeDynSized<Header = HeaderTagHeader> + ?Sized>(
+    tag: &T,
+) -> Vec<u8> {
+    // Not all tags are 8-byte aligned, so `as_bytes()` can't be used.
+    let size = (tag.size() as usize).min(mem::size_of_val(tag));
+    let ptr = core::ptr::addr_of!(*tag).cast::<u8>();
+    // SAFETY: The reported size of the tag doesn't include the uninitialized
+    // trailing padding of the type.
+    let bytes = unsafe { core::slice::from_raw_parts(ptr, size) };
+    let mut bytes = bytes.to_vec();
+    bytes.resize(multiboot2_common::increase_to_alignment(size), 0);
+    bytes
+}
+
 impl Header for HeaderTagHeader {
     fn payload_len(&self) -> usize {
         self.size as usize - mem::size_of::<Self>()
@@ -115,12 +292,109 @@ impl Header for HeaderTagHeader {
     }
 }
 
+/// Common interface of all header tags for dynamic dispatch.
+///
+/// It is implemented for all tags, including tags of an unknown type. Only
+/// sized tags can be used as trait objects, see
+/// [`crate::Multiboot2Header::tag_objects`].
+pub trait HeaderTag: Debug {
+    /// Returns the [`HeaderTagHeader`] of the tag.
+    fn tag_header(&self) -> &HeaderTagHeader;
+
+    /// Returns the raw type of the tag.
+    #[must_use]
+    fn typ_raw(&self) -> u16 {
+        self.tag_header().typ_raw()
+    }
+
+    /// Returns the [`HeaderTagFlag`]s.
+    #[must_use]
+    fn flags(&self) -> HeaderTagFlag {
+        self.tag_header().flags()
+    }
+
+    /// Returns the size.
+    #[must_use]
+    fn size(&self) -> u32 {
+        self.tag_header().size()
+    }
+}
+
+impl<T: MaybeDynSized<Header = HeaderTagHeader> + Debug + ?Sized> HeaderTag for T {
+    fn tag_header(&self) -> &HeaderTagHeader {
+        self.header()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::HeaderTagHeader;
+    use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
+    use std::string::ToString;
+
+    #[test]
+    fn test_display_header_tag_type() {
+        assert_eq!(
+            HeaderTagType::InformationRequest.to_string(),
+            "information-request"
+        );
+        assert_eq!(
+            HeaderTagType::EntryAddressEFI64.to_string(),
+            "entry-address-efi64"
+        );
+        assert_eq!(
+            HeaderTagType::from_u16(0x1337).unwrap_err().to_string(),
+            "Unknown header tag type 0x1337"
+        );
+    }
+
+    #[test]
+    fn test_header_tag_flag() {
+        assert_eq!(HeaderTagFlag::from_bits(0), HeaderTagFlag::Required);
+        assert_eq!(HeaderTagFlag::from_bits(1), HeaderTagFlag::Optional);
+        assert_eq!(HeaderTagFlag::from_bits(0xfffe), HeaderTagFlag::Required);
+        assert_eq!(HeaderTagFlag::from_bits(0xffff), HeaderTagFlag::Optional);
+
+        assert!(HeaderTagFlag::Required.is_required());
+        assert!(!HeaderTagFlag::Required.is_optional());
+        assert!(HeaderTagFlag::Optional.is_optional());
+        assert!(!HeaderTagFlag::Optional.is_required());
+    }
 
     #[test]
     fn test_assert_size() {
         assert_eq!(core::mem::size_of::<HeaderTagHeader>(), 2 + 2 + 4);
     }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn test_tag_to_bytes() {
+        use crate::{
+            tag_to_bytes, Builder, ConsoleHeaderTag, ConsoleHeaderTagFlags, EndHeaderTag,
+            HeaderTagFlag, HeaderTagISA, InformationRequestHeaderTag, MbiTagType,
+            ModuleAlignHeaderTag,
+        };
+
+        let console_tag = ConsoleHeaderTag::new(
+            HeaderTagFlag::Required,
+            ConsoleHeaderTagFlags::ConsoleRequired,
+        );
+        let module_align_tag = ModuleAlignHeaderTag::new(HeaderTagFlag::Optional);
+        let ir_tag = || {
+            InformationRequestHeaderTag::new(HeaderTagFlag::Required, &[MbiTagType::Cmdline.into()])
+        };
+        // `build_into` zeroes the padding, in contrast to `build`.
+        let mut bytes = [0xff; 64];
+        let len = Builder::new(HeaderTagISA::I386)
+            .information_request_tag(ir_tag())
+            .console_tag(console_tag)
+            .module_align_tag(module_align_tag)
+            .build_into(&mut bytes)
+            .unwrap();
+        assert_eq!(len, 16 + 16 + 16 + 8 + 8);
+
+        assert_eq!(tag_to_bytes(&*ir_tag()), &bytes[16..32]);
+        assert_eq!(tag_to_bytes(&console_tag), &bytes[32..48]);
+        assert_eq!(tag_to_bytes(&module_align_tag), &bytes[48..56]);
+        assert_eq!(tag_to_bytes(&EndHeaderTag::new()), &bytes[56..64]);
+    }
 }
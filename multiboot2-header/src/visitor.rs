@@ -0,0 +1,53 @@
+//! Module for [`HeaderTagVisitor`].
+//!
+//! See [`crate::Multiboot2Header::visit`].
+
+use crate::{
+    AddressHeaderTag, ConsoleHeaderTag, EfiBootServiceHeaderTag, EndHeaderTag,
+    EntryAddressHeaderTag, EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag,
+    HeaderTagHeader, InformationRequestHeaderTag, ModuleAlignHeaderTag, RelocatableHeaderTag,
+};
+use multiboot2_common::DynSizedStructure;
+
+/// Visitor over the tags of a Multiboot2 header with one typed method per
+/// tag kind. All methods do nothing by default, so implementors only
+/// override the methods for the tags they are interested in.
+///
+/// See [`crate::Multiboot2Header::visit`].
+pub trait HeaderTagVisitor {
+    /// Visits an [`EndHeaderTag`].
+    fn visit_end(&mut self, _tag: &EndHeaderTag) {}
+
+    /// Visits an [`InformationRequestHeaderTag`].
+    fn visit_information_request(&mut self, _tag: &InformationRequestHeaderTag) {}
+
+    /// Visits an [`AddressHeaderTag`].
+    fn visit_address(&mut self, _tag: &AddressHeaderTag) {}
+
+    /// Visits an [`EntryAddressHeaderTag`].
+    fn visit_entry_address(&mut self, _tag: &EntryAddressHeaderTag) {}
+
+    /// Visits a [`ConsoleHeaderTag`].
+    fn visit_console(&mut self, _tag: &ConsoleHeaderTag) {}
+
+    /// Visits a [`FramebufferHeaderTag`].
+    fn visit_framebuffer(&mut self, _tag: &FramebufferHeaderTag) {}
+
+    /// Visits a [`ModuleAlignHeaderTag`].
+    fn visit_module_align(&mut self, _tag: &ModuleAlignHeaderTag) {}
+
+    /// Visits an [`EfiBootServiceHeaderTag`].
+    fn visit_efi_bs(&mut self, _tag: &EfiBootServiceHeaderTag) {}
+
+    /// Visits an [`EntryEfi32HeaderTag`].
+    fn visit_entry_efi32(&mut self, _tag: &EntryEfi32HeaderTag) {}
+
+    /// Visits an [`EntryEfi64HeaderTag`].
+    fn visit_entry_efi64(&mut self, _tag: &EntryEfi64HeaderTag) {}
+
+    /// Visits a [`RelocatableHeaderTag`].
+    fn visit_relocatable(&mut self, _tag: &RelocatableHeaderTag) {}
+
+    /// Visits a tag whose type is unknown to this crate.
+    fn visit_unknown(&mut self, _tag: &DynSizedStructure<HeaderTagHeader>) {}
+}
@@ -152,6 +152,35 @@ impl FramebufferTag {
         self.bpp
     }
 
+    /// Returns the minimum pitch in bytes of tightly packed lines, i.e.,
+    /// `width * ceil(bpp / 8)`.
+    #[must_use]
+    pub const fn expected_min_pitch(&self) -> u32 {
+        let bytes_per_pixel = (self.bpp as u32 + 7) / 8;
+        self.width.saturating_mul(bytes_per_pixel)
+    }
+
+    /// Returns whether the pitch is at least [`Self::expected_min_pitch`].
+    /// A bigger pitch means that each line is followed by padding bytes.
+    #[must_use]
+    pub const fn pitch_is_valid(&self) -> bool {
+        self.pitch >= self.expected_min_pitch()
+    }
+
+    /// Compares the geometry of two framebuffers, i.e., pitch, width, height,
+    /// bits per pixel, and the type including its color information, but not
+    /// the address. This is useful to check whether a loader honored a
+    /// requested mode.
+    #[must_use]
+    pub fn geometry_eq(&self, other: &Self) -> bool {
+        self.pitch == other.pitch
+            && self.width == other.width
+            && self.height == other.height
+            && self.bpp == other.bpp
+            && self.framebuffer_type == other.framebuffer_type
+            && self.buffer == other.buffer
+    }
+
     /// The type of framebuffer, one of: `Indexed`, `RGB` or `Text`.
     pub fn buffer_type(&self) -> Result<FramebufferType, UnknownFramebufferType> {
         let mut reader = Reader::new(&self.buffer);
@@ -206,6 +235,39 @@ impl FramebufferTag {
             FramebufferTypeId::Text => Ok(FramebufferType::Text),
         }
     }
+
+    /// Returns the palette of an indexed framebuffer as `(index, color)`
+    /// pairs, which is convenient for building color lookup tables.
+    ///
+    /// In contrast to [`Self::buffer_type`], the entries are bounded by the
+    /// declared number of colors, the length of the tag, and the 256 indices
+    /// representable by an `u8`. Returns `None` if the framebuffer is not
+    /// indexed.
+    #[must_use]
+    pub fn palette_entries(&self) -> Option<impl Iterator<Item = (u8, FramebufferColor)> + '_> {
+        if self.framebuffer_type != FramebufferTypeId::Indexed {
+            return None;
+        }
+        let num_colors = match self.buffer.get(0..2)? {
+            &[lo, hi] => u16::from_le_bytes([lo, hi]),
+            _ => return None,
+        };
+        let entries = self.buffer[2..]
+            .chunks_exact(mem::size_of::<FramebufferColor>())
+            .take(usize::from(num_colors).min(usize::from(u8::MAX) + 1))
+            .enumerate()
+            .map(|(index, color)| {
+                (
+                    index as u8,
+                    FramebufferColor {
+                        red: color[0],
+                        green: color[1],
+                        blue: color[2],
+                    },
+                )
+            });
+        Some(entries)
+    }
 }
 
 impl MaybeDynSized for FramebufferTag {
@@ -320,6 +382,20 @@ pub enum FramebufferType<'a> {
 }
 
 impl<'a> FramebufferType<'a> {
+    /// Interprets the dimensions of `tag` as text cells, if this is
+    /// [`FramebufferType::Text`]. Returns `None` for other types.
+    #[must_use]
+    pub const fn text_metrics(&self, tag: &FramebufferTag) -> Option<TextMetrics> {
+        match self {
+            FramebufferType::Text => Some(TextMetrics {
+                cols: tag.width(),
+                rows: tag.height(),
+                cell_bytes: tag.bpp() / 8,
+            }),
+            _ => None,
+        }
+    }
+
     #[must_use]
     #[cfg(feature = "builder")]
     const fn id(&self) -> FramebufferTypeId {
@@ -360,6 +436,18 @@ impl<'a> FramebufferType<'a> {
     }
 }
 
+/// The dimensions of a [`FramebufferType::Text`] framebuffer in text cells.
+/// See [`FramebufferType::text_metrics`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TextMetrics {
+    /// The number of columns, i.e., characters per line.
+    pub cols: u32,
+    /// The number of rows, i.e., lines.
+    pub rows: u32,
+    /// The number of bytes per cell, i.e., per character and its attributes.
+    pub cell_bytes: u8,
+}
+
 /// An RGB color type field.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C)]
@@ -460,4 +548,122 @@ mod tests {
         // Good test for Miri
         dbg!(tag);
     }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn palette_entries() {
+        let colors = [
+            FramebufferColor {
+                red: 255,
+                green: 255,
+                blue: 255,
+            },
+            FramebufferColor {
+                red: 127,
+                green: 42,
+                blue: 73,
+            },
+            FramebufferColor {
+                red: 1,
+                green: 2,
+                blue: 3,
+            },
+        ];
+        let tag = FramebufferTag::new(
+            0x1000,
+            1,
+            1024,
+            1024,
+            8,
+            FramebufferType::Indexed { palette: &colors },
+        );
+        let entries = tag
+            .palette_entries()
+            .unwrap()
+            .collect::<alloc::vec::Vec<_>>();
+        assert_eq!(entries.len(), colors.len());
+        for (i, (index, color)) in entries.into_iter().enumerate() {
+            assert_eq!(usize::from(index), i);
+            assert_eq!(color, colors[i]);
+        }
+
+        let tag = FramebufferTag::new(0x1000, 1, 1024, 1024, 8, FramebufferType::Text);
+        assert!(tag.palette_entries().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn text_metrics() {
+        let tag = FramebufferTag::new(0xb8000, 160, 80, 25, 16, FramebufferType::Text);
+        let metrics = tag.buffer_type().unwrap().text_metrics(&tag);
+        assert_eq!(
+            metrics,
+            Some(TextMetrics {
+                cols: 80,
+                rows: 25,
+                cell_bytes: 2,
+            })
+        );
+
+        let tag = FramebufferTag::new(
+            0x1000,
+            1,
+            1024,
+            1024,
+            8,
+            FramebufferType::Indexed { palette: &[] },
+        );
+        assert_eq!(tag.buffer_type().unwrap().text_metrics(&tag), None);
+    }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn pitch_is_valid() {
+        let rgb = FramebufferType::RGB {
+            red: FramebufferField {
+                position: 16,
+                size: 8,
+            },
+            green: FramebufferField {
+                position: 8,
+                size: 8,
+            },
+            blue: FramebufferField {
+                position: 0,
+                size: 8,
+            },
+        };
+
+        // Tightly packed lines.
+        let tag = FramebufferTag::new(0x1000, 1024 * 3, 1024, 768, 24, rgb.clone());
+        assert_eq!(tag.expected_min_pitch(), 1024 * 3);
+        assert!(tag.pitch_is_valid());
+
+        // Lines with padding.
+        let tag = FramebufferTag::new(0x1000, 4096, 1024, 768, 24, rgb.clone());
+        assert_eq!(tag.expected_min_pitch(), 1024 * 3);
+        assert!(tag.pitch_is_valid());
+
+        // Lines too short for the width.
+        let tag = FramebufferTag::new(0x1000, 1024, 1024, 768, 24, rgb);
+        assert!(!tag.pitch_is_valid());
+
+        // Bits per pixel that are not a multiple of 8 are rounded up.
+        let tag = FramebufferTag::new(0x1000, 2048, 1024, 768, 15, FramebufferType::Text);
+        assert_eq!(tag.expected_min_pitch(), 2048);
+    }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn test_geometry_eq() {
+        let tag = FramebufferTag::new(0x1000, 4096, 1024, 768, 32, FramebufferType::Text);
+        let moved = FramebufferTag::new(0xfd00_0000, 4096, 1024, 768, 32, FramebufferType::Text);
+        assert_ne!(tag, moved);
+        assert!(tag.geometry_eq(&moved));
+
+        let other = FramebufferTag::new(0x1000, 4096, 1024, 600, 32, FramebufferType::Text);
+        assert!(!tag.geometry_eq(&other));
+        let other = FramebufferTag::new(0x1000, 4096, 1024, 768, 24, FramebufferType::Text);
+        assert!(!tag.geometry_eq(&other));
+    }
 }